@@ -0,0 +1,260 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::Deserialize;
+use serde_json::Value;
+use solana_sdk::bs58;
+
+use crate::{
+    parsed_fields, title_case, AccountInfoConfig, Encoding, ResponseWithContext, RpcBatch, RpcCall,
+    RpcClient, RpcError, RpcResult, RpcTransport,
+};
+
+/// Program data can take megabytes, only its start is rendered
+pub const MAX_DISPLAYED_BYTES: usize = 4096;
+
+type EncodedAccountInfo = ResponseWithContext<Option<AccountInfo<(String, Encoding)>>>;
+type ParsedAccountInfo = ResponseWithContext<Option<AccountInfo<JsonParsedData>>>;
+
+/// Loads an account of the active cluster, `None` if it does not exist.
+/// The raw data and the data parsed by the node are loaded in one batch.
+pub async fn load_account_info<T: RpcTransport>(
+    client: &RpcClient<T>,
+    address: &str,
+) -> RpcResult<Option<AccountInfo<AccountData>>> {
+    let mut batch = RpcBatch::new();
+
+    let encoded = batch.add(RpcCall::<EncodedAccountInfo>::get_account_info(
+        address,
+        AccountInfoConfig::new(Encoding::Base64),
+    ));
+    let parsed = batch.add(RpcCall::<ParsedAccountInfo>::get_account_info(
+        address,
+        AccountInfoConfig::new(Encoding::JsonParsed),
+    ));
+
+    let mut responses = client.send_batch(batch).await?;
+
+    let Some(account) = responses.take(encoded)?.value else {
+        return Ok(None);
+    };
+
+    let bytes = BASE64_STANDARD
+        .decode(&account.data.0)
+        .map_err(|error| RpcError::Deserialize(error.to_string()))?;
+
+    // Accounts the node cannot parse come back in base64
+    let parsed = responses
+        .take(parsed)?
+        .value
+        .and_then(|account| match account.data {
+            JsonParsedData::Parsed(parsed) => Some(parsed),
+            JsonParsedData::Encoded(..) => None,
+        });
+
+    Ok(Some(account.with_data(AccountData { bytes, parsed })))
+}
+
+/// An account as returned by `getAccountInfo`, `D` depends on the encoding
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfo<D> {
+    pub lamports: u64,
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub data: D,
+}
+
+impl<D> AccountInfo<D> {
+    pub fn with_data<E>(self, data: E) -> AccountInfo<E> {
+        AccountInfo {
+            lamports: self.lamports,
+            owner: self.owner,
+            executable: self.executable,
+            rent_epoch: self.rent_epoch,
+            data,
+        }
+    }
+}
+
+impl AccountInfo<AccountData> {
+    pub fn data_size(&self) -> usize {
+        self.data.bytes.len()
+    }
+
+    /// `None` unless the owner is a program the page knows how to decode
+    pub fn kind(&self) -> Option<AccountKind> {
+        let parsed = self.data.parsed.as_ref()?;
+        let account_type = parsed.parsed.get("type").and_then(Value::as_str);
+
+        match (parsed.program.as_str(), account_type) {
+            ("spl-token" | "spl-token-2022", Some("mint")) => Some(AccountKind::TokenMint),
+            ("spl-token" | "spl-token-2022", Some("account")) => Some(AccountKind::TokenAccount),
+            ("stake", _) => Some(AccountKind::Stake),
+            ("vote", _) => Some(AccountKind::Vote),
+            ("nonce", _) => Some(AccountKind::Nonce),
+            ("bpf-upgradeable-loader", _) => Some(AccountKind::UpgradeableLoader),
+            _ => None,
+        }
+    }
+
+    /// The decoded fields of a [AccountKind], empty for other accounts
+    pub fn decoded_fields(&self) -> Vec<(String, String)> {
+        if self.kind().is_none() {
+            return Vec::default();
+        }
+
+        let Some(parsed) = self.data.parsed.as_ref() else {
+            return Vec::default();
+        };
+
+        let mut fields = parsed
+            .parsed
+            .get("type")
+            .and_then(Value::as_str)
+            .map(|account_type| vec![("Type".to_string(), title_case(account_type))])
+            .unwrap_or_default();
+
+        // The executable of program data accounts is already shown as raw data
+        if let Some(info) = parsed.parsed.get("info") {
+            fields.extend(
+                parsed_fields(info)
+                    .into_iter()
+                    .filter(|(name, _)| name != "Data"),
+            );
+        }
+
+        fields
+    }
+}
+
+/// The data of an account in the `jsonParsed` encoding
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum JsonParsedData {
+    Parsed(ParsedAccountData),
+    Encoded(String, Encoding),
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedAccountData {
+    /// The parser the node used, eg. `spl-token` or `stake`
+    pub program: String,
+    pub parsed: Value,
+    pub space: u64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AccountData {
+    pub bytes: Vec<u8>,
+    pub parsed: Option<ParsedAccountData>,
+}
+
+/// Accounts of programs whose data is decoded on the account page
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AccountKind {
+    TokenMint,
+    TokenAccount,
+    Stake,
+    Vote,
+    Nonce,
+    UpgradeableLoader,
+}
+
+impl core::fmt::Display for AccountKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TokenMint => write!(f, "TOKEN MINT"),
+            Self::TokenAccount => write!(f, "TOKEN ACCOUNT"),
+            Self::Stake => write!(f, "STAKE ACCOUNT"),
+            Self::Vote => write!(f, "VOTE ACCOUNT"),
+            Self::Nonce => write!(f, "NONCE ACCOUNT"),
+            Self::UpgradeableLoader => write!(f, "UPGRADEABLE LOADER"),
+        }
+    }
+}
+
+/// The encodings raw account data can be viewed in
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum DataEncoding {
+    #[default]
+    Hex,
+    Base64,
+    Base58,
+}
+
+impl DataEncoding {
+    pub const ALL: [Self; 3] = [Self::Hex, Self::Base64, Self::Base58];
+
+    pub fn encode(&self, data: &[u8]) -> String {
+        match self {
+            Self::Hex => data
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<Vec<String>>()
+                .join(" "),
+            Self::Base64 => BASE64_STANDARD.encode(data),
+            Self::Base58 => bs58::encode(data).into_string(),
+        }
+    }
+}
+
+impl core::fmt::Display for DataEncoding {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Hex => write!(f, "HEX"),
+            Self::Base64 => write!(f, "BASE64"),
+            Self::Base58 => write!(f, "BASE58"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{block_on, MockTransport, MINT, OWNER};
+
+    const FIXTURES: &str = include_str!("../fixtures/rpc/accounts.json");
+
+    fn load(address: &str) -> AccountInfo<AccountData> {
+        let client = RpcClient::with_transport(MockTransport::from_json(FIXTURES).unwrap());
+
+        block_on(load_account_info(&client, address))
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn decodes_a_token_mint() {
+        let mint = load(MINT);
+
+        assert_eq!(mint.lamports, 1461600);
+        assert_eq!(mint.rent_epoch, u64::MAX);
+        assert_eq!(mint.data_size(), 82);
+        assert_eq!(mint.kind(), Some(AccountKind::TokenMint));
+
+        let fields = mint.decoded_fields();
+        assert_eq!(fields[0], ("Type".to_string(), "Mint".to_string()));
+        assert!(fields.contains(&("Mint Authority".to_string(), OWNER.to_string())));
+        assert!(fields.contains(&("Freeze Authority".to_string(), "None".to_string())));
+    }
+
+    #[test]
+    fn accounts_without_a_parser_only_have_raw_data() {
+        let wallet = load(OWNER);
+
+        assert_eq!(wallet.owner, "11111111111111111111111111111111");
+        assert_eq!(wallet.data_size(), 0);
+        assert_eq!(wallet.kind(), None);
+        assert!(wallet.decoded_fields().is_empty());
+    }
+
+    #[test]
+    fn encodes_raw_data() {
+        let data = [1u8, 0, 0, 0, 126, 140];
+
+        assert_eq!(DataEncoding::Hex.encode(&data), "01 00 00 00 7e 8c");
+        assert_eq!(DataEncoding::Base64.encode(&data), "AQAAAH6M");
+        assert_eq!(DataEncoding::Base58.encode(&data), "VtB5fAB");
+    }
+}
@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use wallet_adapter::{WalletError, WalletResult};
+
+/// The number of decimals of SOL, one SOL is 10^9 lamports
+pub const SOL_DECIMALS: u8 = 9;
+
+/// An exact amount of lamports. Parses and displays as SOL
+/// without going through floats.
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Lamports(u64);
+
+impl Lamports {
+    pub const fn new(lamports: u64) -> Self {
+        Self(lamports)
+    }
+
+    pub const fn from_sol(sol: u64) -> Self {
+        Self(sol * LAMPORTS_PER_SOL)
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// Accepts `1.25` and `1.25 SOL`
+impl FromStr for Lamports {
+    type Err = WalletError;
+
+    fn from_str(sol: &str) -> WalletResult<Self> {
+        let sol = sol.trim();
+        let sol = sol
+            .strip_suffix("SOL")
+            .or_else(|| sol.strip_suffix("sol"))
+            .unwrap_or(sol);
+
+        parse_decimal(sol, SOL_DECIMALS).map(Self)
+    }
+}
+
+/// Formats the amount as SOL, eg. `1.25`
+impl core::fmt::Display for Lamports {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", format_decimal(self.0 as u128, SOL_DECIMALS))
+    }
+}
+
+/// An exact amount of a token in its smallest unit along with the
+/// decimals of its mint
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct TokenAmount {
+    raw: u64,
+    decimals: u8,
+}
+
+impl TokenAmount {
+    pub const fn new(raw: u64, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    /// Parses a UI amount like `12.5` of a mint with `decimals`
+    pub fn parse(amount: &str, decimals: u8) -> WalletResult<Self> {
+        parse_decimal(amount.trim(), decimals).map(|raw| Self::new(raw, decimals))
+    }
+
+    pub fn raw(&self) -> u64 {
+        self.raw
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.raw == 0
+    }
+}
+
+/// Formats the UI amount, eg. `12.5`
+impl core::fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", format_decimal(self.raw as u128, self.decimals))
+    }
+}
+
+/// Parses a decimal like `1.25` into an integer of the smallest unit,
+/// rejecting more fractional digits than `decimals` and overflows
+pub(crate) fn parse_decimal(amount: &str, decimals: u8) -> WalletResult<u64> {
+    let invalid = || WalletError::Op(format!("`{amount}` is not a valid amount"));
+
+    let amount = amount.trim();
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+    if !whole
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    if fraction.len() > decimals as usize {
+        return Err(WalletError::Op(format!(
+            "`{amount}` has more than {decimals} decimals"
+        )));
+    }
+
+    let too_large = || WalletError::Op(format!("`{amount}` is too large"));
+
+    let unit = 10u64.checked_pow(decimals as u32).ok_or_else(too_large)?;
+    let whole = match whole {
+        "" => 0,
+        whole => whole.parse::<u64>().map_err(|_| too_large())?,
+    };
+    let fraction = match fraction {
+        "" => 0,
+        fraction => {
+            fraction.parse::<u64>().map_err(|_| invalid())?
+                * 10u64.pow((decimals as usize - fraction.len()) as u32)
+        }
+    };
+
+    whole
+        .checked_mul(unit)
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or_else(too_large)
+}
+
+/// Formats an integer of the smallest unit with `decimals`,
+/// trailing zeros of the fraction are left out
+pub(crate) fn format_decimal(amount: u128, decimals: u8) -> String {
+    // A u128 has at most 39 digits, any larger unit leaves no whole part
+    let (whole, fraction) = match 10u128.checked_pow(decimals as u32) {
+        Some(unit) => (amount / unit, amount % unit),
+        None => (0, amount),
+    };
+
+    let fraction = format!("{fraction:0width$}", width = decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sol_exactly() {
+        assert_eq!("1.25 SOL".parse(), Ok(Lamports::new(1_250_000_000)));
+        assert_eq!("1.25".parse(), Ok(Lamports::new(1_250_000_000)));
+        assert_eq!(" .5 sol ".parse(), Ok(Lamports::new(500_000_000)));
+        assert_eq!("0.000000001".parse(), Ok(Lamports::new(1)));
+        assert_eq!("18446744073.709551615".parse(), Ok(Lamports::new(u64::MAX)));
+
+        for invalid in ["", ".", "1.2.3", "-1", "1e9", "0.0000000001", "18446744074"] {
+            assert!(
+                invalid.parse::<Lamports>().is_err(),
+                "`{invalid}` should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn formats_without_floats() {
+        assert_eq!(Lamports::new(1_250_000_000).to_string(), "1.25");
+        assert_eq!(Lamports::new(1).to_string(), "0.000000001");
+        assert_eq!(Lamports::from_sol(2).to_string(), "2");
+        assert_eq!(Lamports::new(u64::MAX).to_string(), "18446744073.709551615");
+    }
+
+    #[test]
+    fn token_amounts_use_their_decimals() {
+        let amount = TokenAmount::parse("12.5", 6).unwrap();
+
+        assert_eq!(amount, TokenAmount::new(12_500_000, 6));
+        assert_eq!(amount.to_string(), "12.5");
+        assert_eq!(TokenAmount::new(5, 0).to_string(), "5");
+        assert!(TokenAmount::parse("0.5", 0).is_err());
+    }
+
+    #[test]
+    fn formats_decimals_beyond_u128() {
+        assert_eq!(
+            TokenAmount::new(5, 39).to_string(),
+            format!("0.{}5", "0".repeat(38))
+        );
+        assert_eq!(
+            TokenAmount::new(5, 255).to_string(),
+            format!("0.{}5", "0".repeat(254))
+        );
+        assert_eq!(TokenAmount::new(0, 255).to_string(), "0");
+    }
+}
@@ -2,12 +2,15 @@ use std::collections::VecDeque;
 
 use dioxus::prelude::*;
 use gloo_timers::callback::Timeout;
+use solana_sdk::pubkey::Pubkey;
 use wallet_adapter::{ConnectionInfo, WalletAdapter};
 
 use crate::{
     views::{AccountState, ClusterNetState},
-    Accounts, AdapterCluster, ClusterStore, Clusters, Dashboard, Extras, Footer, Header,
-    NotificationInfo,
+    AccountDetail, Accounts, AdapterCluster, ClusterStore, Clusters, Dashboard,
+    EndpointHealthStore, Extras, Footer, Header, NotificationInfo, PendingTransactions,
+    PreparedTransaction, PriorityFeeStore, PubSubClient, TokenMetadataCache, TransactionDetail,
+    TransactionReview,
 };
 
 const FAVICON: Asset = asset!("/assets/favicon.png");
@@ -20,6 +23,9 @@ pub(crate) static WALLET_ADAPTER: GlobalSignal<WalletAdapter> =
 pub(crate) static CLUSTER_STORAGE: GlobalSignal<ClusterStore> =
     Signal::global(|| ClusterStore::new(Vec::default()));
 
+pub(crate) static ENDPOINT_HEALTH: GlobalSignal<EndpointHealthStore> =
+    Signal::global(|| EndpointHealthStore::default());
+
 pub(crate) static GLOBAL_MESSAGE: GlobalSignal<VecDeque<NotificationInfo>> =
     Signal::global(|| VecDeque::default());
 
@@ -34,6 +40,28 @@ pub(crate) static CLUSTER_NET_STATE: GlobalSignal<ClusterNetState> =
 pub(crate) static ACTIVE_CONNECTION: GlobalSignal<ConnectionInfo> =
     Signal::global(|| ConnectionInfo::default());
 
+pub(crate) static PUBSUB: GlobalSignal<Option<PubSubClient>> = Signal::global(|| Option::default());
+
+pub(crate) static CLUSTER_SLOT: GlobalSignal<Option<u64>> = Signal::global(|| Option::default());
+
+pub(crate) static PENDING_TRANSACTIONS: GlobalSignal<PendingTransactions> =
+    Signal::global(|| PendingTransactions::default());
+
+pub(crate) static TOKEN_METADATA: GlobalSignal<TokenMetadataCache> =
+    Signal::global(|| TokenMetadataCache::default());
+
+/// The priority fee picked for each cluster, restored from the local storage
+pub(crate) static PRIORITY_FEES: GlobalSignal<PriorityFeeStore> =
+    Signal::global(PriorityFeeStore::load);
+
+/// The nonce account new transactions take their blockhash from, if any
+pub(crate) static DURABLE_NONCE: GlobalSignal<Option<Pubkey>> =
+    Signal::global(|| Option::default());
+
+/// Transactions waiting for the user to approve their simulation, in order
+pub(crate) static TRANSACTION_REVIEW: GlobalSignal<VecDeque<PreparedTransaction>> =
+    Signal::global(|| VecDeque::default());
+
 #[component]
 pub(crate) fn App() -> Element {
     let wallet_event_listener = WALLET_ADAPTER.read().events().clone();
@@ -68,7 +96,7 @@ pub(crate) fn App() -> Element {
         div { class: "w-full flex min-h-screen font-[sans-serif] dark:bg-rich-black bg-white text-black dark:text-white",
 
             Notification {}
-
+            TransactionReview {}
 
             div { class: "flex flex-col w-full min-h-full justify-between items-center",
                 Router::<Route> {}
@@ -153,6 +181,10 @@ pub enum Route {
         Clusters(),
         #[route("/extras")]
         Extras(),
+        #[route("/tx/:signature")]
+        TransactionDetail { signature: String },
+        #[route("/address/:pubkey")]
+        AccountDetail { pubkey: String },
     #[end_layout]
     #[route("/:..route")]
     PageNotFound { route: Vec<String> },
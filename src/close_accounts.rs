@@ -0,0 +1,260 @@
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+};
+use wallet_adapter::{WalletError, WalletResult};
+
+use crate::{
+    budgeted_size, budgeted_transaction, program_id, Lamports, PreparedTransaction, RpcClient,
+    TokenAccountResponse, TokenProgram, TransactionLifetime,
+};
+
+/// `CloseAccount` in the instruction enum shared by both token programs
+const CLOSE_ACCOUNT: u8 = 9;
+
+/// A token account with nothing left in it, closing it returns its rent
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct EmptyTokenAccount {
+    pub address: Pubkey,
+    pub program: TokenProgram,
+    pub rent: Lamports,
+}
+
+/// The accounts of `token_accounts` that can be closed
+pub fn empty_token_accounts(token_accounts: &[TokenAccountResponse]) -> Vec<EmptyTokenAccount> {
+    token_accounts
+        .iter()
+        .filter(|token_account| token_account.is_closable())
+        .filter_map(|token_account| {
+            Some(EmptyTokenAccount {
+                address: token_account.ata_address().parse().ok()?,
+                program: token_account.program(),
+                rent: token_account.lamports(),
+            })
+        })
+        .collect()
+}
+
+/// The lamports returned to the owner once `accounts` are closed
+pub fn reclaimable_rent(accounts: &[EmptyTokenAccount]) -> Lamports {
+    Lamports::new(accounts.iter().map(|account| account.rent.get()).sum())
+}
+
+/// Closes `account` and sends its lamports to `destination`
+pub fn close_account(
+    program: TokenProgram,
+    account: &Pubkey,
+    destination: &Pubkey,
+    owner: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id(program.id()),
+        &[CLOSE_ACCOUNT],
+        vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+    )
+}
+
+/// Groups the close instructions of `accounts` into as few transactions as
+/// possible, each one fitting in a packet once signed by `owner`
+pub fn close_account_batches(
+    accounts: &[EmptyTokenAccount],
+    owner: Pubkey,
+) -> Vec<Vec<Instruction>> {
+    let mut batches = Vec::<Vec<Instruction>>::new();
+    let mut batch = Vec::<Instruction>::new();
+
+    for account in accounts {
+        let instruction = close_account(account.program, &account.address, &owner, &owner);
+
+        batch.push(instruction);
+        if batch.len() > 1 && transaction_size(&batch, &owner) > PACKET_DATA_SIZE {
+            let overflow = batch.pop();
+            batches.push(core::mem::take(&mut batch));
+            batch.extend(overflow);
+        }
+    }
+
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+
+    batches
+}
+
+/// The size of the signed transaction, room is kept for an advance nonce
+/// instruction since the nonce can be picked after the batches are planned
+fn transaction_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+    let nonce_account = Pubkey::new_from_array([u8::MAX; 32]);
+
+    budgeted_size(instructions, payer, &[], Some(&nonce_account)).unwrap_or(usize::MAX)
+}
+
+/// Builds one transaction per batch with the same blockhash, they are
+/// reviewed and signed one after the other
+pub async fn prepare_close_accounts(
+    accounts: &[EmptyTokenAccount],
+    public_key_bytes: [u8; 32],
+) -> WalletResult<Vec<PreparedTransaction>> {
+    let client = RpcClient::new();
+    let owner = Pubkey::new_from_array(public_key_bytes);
+
+    let batches = close_account_batches(accounts, owner);
+    let lifetime = TransactionLifetime::resolve(&client, &owner).await?;
+
+    // The first transaction to land advances the nonce and invalidates the others
+    if lifetime.is_durable() && batches.len() > 1 {
+        return Err(WalletError::Op(format!(
+            "A durable nonce only covers one transaction, closing these accounts takes {}",
+            batches.len()
+        )));
+    }
+
+    let mut prepared = Vec::<PreparedTransaction>::new();
+
+    for batch in batches {
+        let tx = budgeted_transaction(&client, &batch, &owner, &[], &lifetime).await?;
+
+        prepared.push(PreparedTransaction::send(
+            tx,
+            lifetime.last_valid_block_height(),
+            &format!("Close {} token accounts", batch.len()),
+        ));
+    }
+
+    Ok(prepared)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{token_account, OWNER, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+    fn token_account_response(
+        address: Pubkey,
+        program: &str,
+        amount: u64,
+        state: &str,
+        extensions: serde_json::Value,
+    ) -> TokenAccountResponse {
+        let mut account = token_account(OWNER, amount);
+        account["owner"] = json!(program);
+        account["data"]["parsed"]["info"]["mint"] = json!(Pubkey::new_unique().to_string());
+        account["data"]["parsed"]["info"]["state"] = json!(state);
+        account["data"]["parsed"]["info"]["extensions"] = extensions;
+
+        serde_json::from_value(json!({ "pubkey": address.to_string(), "account": account }))
+            .unwrap()
+    }
+
+    #[test]
+    fn finds_closable_accounts() {
+        let empty = Pubkey::new_unique();
+        let empty_2022 = Pubkey::new_unique();
+
+        let token_accounts = [
+            token_account_response(empty, TOKEN_PROGRAM_ID, 0, "initialized", json!([])),
+            token_account_response(
+                empty_2022,
+                TOKEN_2022_PROGRAM_ID,
+                0,
+                "initialized",
+                json!([{ "extension": "immutableOwner" }]),
+            ),
+            token_account_response(
+                Pubkey::new_unique(),
+                TOKEN_PROGRAM_ID,
+                1,
+                "initialized",
+                json!([]),
+            ),
+            token_account_response(
+                Pubkey::new_unique(),
+                TOKEN_PROGRAM_ID,
+                0,
+                "frozen",
+                json!([]),
+            ),
+            token_account_response(
+                Pubkey::new_unique(),
+                TOKEN_2022_PROGRAM_ID,
+                0,
+                "initialized",
+                json!([{ "extension": "transferFeeAmount", "state": { "withheldAmount": 3 } }]),
+            ),
+        ];
+
+        let accounts = empty_token_accounts(&token_accounts);
+
+        assert_eq!(
+            accounts,
+            [
+                EmptyTokenAccount {
+                    address: empty,
+                    program: TokenProgram::Token,
+                    rent: Lamports::new(2039280),
+                },
+                EmptyTokenAccount {
+                    address: empty_2022,
+                    program: TokenProgram::Token2022,
+                    rent: Lamports::new(2039280),
+                },
+            ]
+        );
+        assert_eq!(reclaimable_rent(&accounts), Lamports::new(4078560));
+    }
+
+    #[test]
+    fn batches_fit_in_a_packet() {
+        let owner = Pubkey::new_unique();
+        let accounts = (0..60)
+            .map(|index| EmptyTokenAccount {
+                address: Pubkey::new_unique(),
+                program: if index % 2 == 0 {
+                    TokenProgram::Token
+                } else {
+                    TokenProgram::Token2022
+                },
+                rent: Lamports::new(2039280),
+            })
+            .collect::<Vec<EmptyTokenAccount>>();
+
+        let batches = close_account_batches(&accounts, owner);
+
+        assert!(batches.len() > 1);
+        assert_eq!(batches.iter().map(Vec::len).sum::<usize>(), accounts.len());
+
+        for batch in batches.iter() {
+            assert!(transaction_size(batch, &owner) <= PACKET_DATA_SIZE);
+        }
+        // Every batch but the last is full
+        for batch in batches[..batches.len() - 1].iter() {
+            let mut larger = batch.clone();
+            larger.push(close_account(
+                TokenProgram::Token,
+                &Pubkey::new_unique(),
+                &owner,
+                &owner,
+            ));
+            assert!(transaction_size(&larger, &owner) > PACKET_DATA_SIZE);
+        }
+
+        let instruction = &batches[0][0];
+        assert_eq!(instruction.data, [9]);
+        assert_eq!(
+            instruction.accounts[0],
+            AccountMeta::new(accounts[0].address, false)
+        );
+        assert_eq!(instruction.accounts[1], AccountMeta::new(owner, false));
+        assert_eq!(
+            instruction.accounts[2],
+            AccountMeta::new_readonly(owner, true)
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use dioxus::prelude::*;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use wallet_adapter::{
@@ -29,7 +31,7 @@ impl ClusterStore {
     pub fn add_cluster(&mut self, cluster: AdapterCluster) -> Result<&mut Self, String> {
         let cluster_exists = self.clusters.iter().any(|inner_cluster| {
             inner_cluster.name.as_bytes() == cluster.name.as_bytes()
-                || inner_cluster.endpoint.as_bytes() == cluster.endpoint.as_bytes()
+                || inner_cluster.endpoint().as_bytes() == cluster.endpoint().as_bytes()
         });
 
         if cluster_exists {
@@ -76,7 +78,12 @@ impl ClusterStore {
 pub(crate) struct AdapterCluster {
     name: String,
     cluster: Cluster,
-    endpoint: String,
+    /// Tried in order, the first one is the primary endpoint
+    endpoints: Vec<String>,
+    ws_endpoint: Option<String>,
+    /// Sent with every RPC request to the cluster. Browsers cannot
+    /// set headers on a WebSocket so PubSub does not use them.
+    headers: Vec<ClusterHeader>,
 }
 
 impl AdapterCluster {
@@ -96,8 +103,33 @@ impl AdapterCluster {
         self
     }
 
+    /// Sets the primary endpoint, replacing any fallbacks
     pub fn add_endpoint(mut self, endpoint: &str) -> Self {
-        self.endpoint = endpoint.to_string();
+        self.endpoints = vec![endpoint.to_string()];
+
+        self
+    }
+
+    /// Adds an endpoint requests fail over to when the ones before it are unhealthy
+    pub fn add_fallback_endpoint(mut self, endpoint: &str) -> Self {
+        if !self.endpoints.iter().any(|current| current == endpoint) {
+            self.endpoints.push(endpoint.to_string());
+        }
+
+        self
+    }
+
+    pub fn add_ws_endpoint(mut self, ws_endpoint: &str) -> Self {
+        self.ws_endpoint = Some(ws_endpoint.to_string());
+
+        self
+    }
+
+    /// Adds a header to every request, replacing a header with the same name
+    pub fn add_header(mut self, header: ClusterHeader) -> Self {
+        self.headers
+            .retain(|current| !current.name.eq_ignore_ascii_case(&header.name));
+        self.headers.push(header);
 
         self
     }
@@ -108,32 +140,83 @@ impl AdapterCluster {
     pub fn cluster(&self) -> Cluster {
         self.cluster
     }
+    /// The primary endpoint
     pub fn endpoint(&self) -> &str {
-        self.endpoint.as_str()
+        self.endpoints
+            .first()
+            .map(|endpoint| endpoint.as_str())
+            .unwrap_or_default()
+    }
+    pub fn endpoints(&self) -> &[String] {
+        self.endpoints.as_slice()
+    }
+    pub fn headers(&self) -> &[ClusterHeader] {
+        self.headers.as_slice()
+    }
+    /// The PubSub endpoint, derived from [Self::endpoint] unless one was set
+    pub fn ws_endpoint(&self) -> String {
+        self.ws_endpoint
+            .clone()
+            .unwrap_or_else(|| Self::derive_ws_endpoint(self.endpoint()))
     }
     pub fn identifier(&self) -> String {
         self.to_string()
     }
 
+    /// Whether the primary endpoint needs credentials, in its URL or in headers
+    pub fn has_credentials(&self) -> bool {
+        !self.headers.is_empty() || mask_endpoint(self.endpoint()) != self.endpoint()
+    }
+
+    /// The explorer query selecting this cluster. Endpoints with credentials
+    /// are left out so links never hand them to the explorer.
     pub fn query_string(&self) -> String {
         if self.name.as_bytes() == self.cluster.to_string().as_bytes()
             && self.cluster != Cluster::LocalNet
         {
             String::new() + "?cluster=" + self.cluster.to_string().as_str()
+        } else if self.has_credentials() {
+            String::from("?cluster=custom")
         } else {
             String::new()
                 + "?cluster=custom&customUrl="
-                + utf8_percent_encode(self.endpoint.as_str(), NON_ALPHANUMERIC)
+                + utf8_percent_encode(self.endpoint(), NON_ALPHANUMERIC)
                     .to_string()
                     .as_str()
         }
     }
 
+    /// Switches the scheme to `ws`/`wss` and, like the Solana CLI, moves an
+    /// explicit port up by one since validators serve PubSub on `rpc port + 1`
+    fn derive_ws_endpoint(endpoint: &str) -> String {
+        let (scheme, rest) = if let Some(rest) = endpoint.strip_prefix("https://") {
+            ("wss://", rest)
+        } else if let Some(rest) = endpoint.strip_prefix("http://") {
+            ("ws://", rest)
+        } else {
+            return endpoint.to_string();
+        };
+
+        let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+
+        let host = match host.rsplit_once(':') {
+            Some((hostname, port)) => match port.parse::<u16>() {
+                Ok(port) => hostname.to_string() + ":" + &port.saturating_add(1).to_string(),
+                Err(_) => host.to_string(),
+            },
+            None => host.to_string(),
+        };
+
+        String::new() + scheme + &host + path
+    }
+
     pub fn devnet() -> Self {
         AdapterCluster {
             name: "devnet".to_string(),
             cluster: Cluster::DevNet,
-            endpoint: Cluster::DevNet.endpoint().to_string(),
+            endpoints: vec![Cluster::DevNet.endpoint().to_string()],
+            ws_endpoint: None,
+            headers: Vec::default(),
         }
     }
 
@@ -141,7 +224,9 @@ impl AdapterCluster {
         AdapterCluster {
             name: "mainnet".to_string(),
             cluster: Cluster::MainNet,
-            endpoint: Cluster::MainNet.endpoint().to_string(),
+            endpoints: vec![Cluster::MainNet.endpoint().to_string()],
+            ws_endpoint: None,
+            headers: Vec::default(),
         }
     }
 
@@ -149,7 +234,9 @@ impl AdapterCluster {
         AdapterCluster {
             name: "testnet".to_string(),
             cluster: Cluster::TestNet,
-            endpoint: Cluster::TestNet.endpoint().to_string(),
+            endpoints: vec![Cluster::TestNet.endpoint().to_string()],
+            ws_endpoint: None,
+            headers: Vec::default(),
         }
     }
 
@@ -157,7 +244,9 @@ impl AdapterCluster {
         AdapterCluster {
             name: "localnet".to_string(),
             cluster: Cluster::LocalNet,
-            endpoint: Cluster::LocalNet.endpoint().to_string(),
+            endpoints: vec![Cluster::LocalNet.endpoint().to_string()],
+            ws_endpoint: None,
+            headers: Vec::default(),
         }
     }
 }
@@ -173,3 +262,392 @@ impl std::fmt::Display for AdapterCluster {
         write!(f, "{}", self.cluster.display())
     }
 }
+
+/// A header the RPC provider of a cluster expects, eg. `Authorization`
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub(crate) struct ClusterHeader {
+    name: String,
+    value: String,
+}
+
+impl ClusterHeader {
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            value: value.trim().to_string(),
+        }
+    }
+
+    /// Parses a `Name: value` line, the name has to be a valid HTTP header name
+    pub fn parse(line: &str) -> Option<Self> {
+        let (name, value) = line.split_once(':')?;
+        let header = Self::new(name, value);
+
+        let valid_name = !header.name.is_empty()
+            && header
+                .name
+                .chars()
+                .all(|char| char.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(char));
+
+        (valid_name && !header.value.is_empty()).then_some(header)
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn value(&self) -> &str {
+        self.value.as_str()
+    }
+
+    /// Credentials are recognized by the header name
+    pub fn is_secret(&self) -> bool {
+        is_secret_name(&self.name)
+    }
+
+    /// The value to show in the UI, secrets only keep their last characters
+    pub fn display_value(&self) -> String {
+        if self.is_secret() {
+            mask_secret(&self.value)
+        } else {
+            self.value.clone()
+        }
+    }
+}
+
+fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+
+    ["auth", "key", "token", "secret", "password"]
+        .iter()
+        .any(|secret| name.contains(secret))
+}
+
+/// Hides all but the last 4 characters, short secrets are hidden completely
+pub(crate) fn mask_secret(secret: &str) -> String {
+    const VISIBLE: usize = 4;
+
+    let chars = secret.chars().collect::<Vec<char>>();
+
+    if chars.len() <= VISIBLE * 2 {
+        "••••••••".to_string()
+    } else {
+        "••••••••".to_string() + &chars[chars.len() - VISIBLE..].iter().collect::<String>()
+    }
+}
+
+/// Path segments long enough to be an API key and mixing letters and digits,
+/// eg. `https://rpc.example.com/v2/<token>`
+fn is_secret_segment(segment: &str) -> bool {
+    segment.len() >= 16
+        && segment
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '-' || char == '_')
+        && segment.chars().any(|char| char.is_ascii_digit())
+        && segment.chars().any(|char| char.is_ascii_alphabetic())
+}
+
+/// Masks the API keys providers put in the path or the query string of an
+/// endpoint, eg. `https://rpc.example.com/<token>/?api-key=...`
+pub(crate) fn mask_endpoint(endpoint: &str) -> String {
+    let (base, query) = match endpoint.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (endpoint, None),
+    };
+
+    let host_start = base.find("://").map(|index| index + 3).unwrap_or_default();
+    let path_start = base[host_start..]
+        .find('/')
+        .map(|index| host_start + index)
+        .unwrap_or(base.len());
+    let (host, path) = base.split_at(path_start);
+
+    let path = path
+        .split('/')
+        .map(|segment| {
+            if is_secret_segment(segment) {
+                mask_secret(segment)
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("/");
+    let base = host.to_string() + &path;
+
+    let Some(query) = query else {
+        return base;
+    };
+
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) if is_secret_name(name) => {
+                name.to_string() + "=" + &mask_secret(value)
+            }
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("&");
+
+    base + "?" + &query
+}
+
+/// Base delay an endpoint is skipped for after a failure,
+/// doubled for every consecutive failure
+const UNHEALTHY_DELAY_MS: u64 = 5_000;
+const MAX_UNHEALTHY_DELAY_MS: u64 = 60_000;
+
+/// Latency and error rate [FetchReq](crate::FetchReq) observed for one endpoint
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct EndpointHealth {
+    requests: u32,
+    failures: u32,
+    consecutive_failures: u32,
+    /// Moving average of the latency of successful requests
+    latency_ms: Option<u32>,
+    unhealthy_until_ms: Option<u64>,
+}
+
+impl EndpointHealth {
+    pub fn record_success(&mut self, latency_ms: u32) -> &mut Self {
+        self.requests += 1;
+        self.consecutive_failures = 0;
+        self.unhealthy_until_ms.take();
+        self.latency_ms = Some(match self.latency_ms {
+            Some(average) => (average.saturating_mul(4) + latency_ms) / 5,
+            None => latency_ms,
+        });
+
+        self
+    }
+
+    pub fn record_failure(&mut self, now_ms: u64) -> &mut Self {
+        self.requests += 1;
+        self.failures += 1;
+        self.consecutive_failures += 1;
+
+        let delay = UNHEALTHY_DELAY_MS
+            .saturating_mul(1 << (self.consecutive_failures - 1).min(8))
+            .min(MAX_UNHEALTHY_DELAY_MS);
+        self.unhealthy_until_ms = Some(now_ms + delay);
+
+        self
+    }
+
+    pub fn is_healthy(&self, now_ms: u64) -> bool {
+        self.unhealthy_until_ms
+            .map(|unhealthy_until| now_ms >= unhealthy_until)
+            .unwrap_or(true)
+    }
+
+    pub fn latency_ms(&self) -> Option<u32> {
+        self.latency_ms
+    }
+
+    pub fn requests(&self) -> u32 {
+        self.requests
+    }
+
+    /// Failed requests in percent
+    pub fn error_rate(&self) -> u32 {
+        if self.requests == 0 {
+            0
+        } else {
+            self.failures * 100 / self.requests
+        }
+    }
+}
+
+/// Health of every endpoint requests were sent to, keyed by URL. Kept apart
+/// from [ClusterStore] so recording a request does not rerun the effects
+/// that watch the active cluster.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub(crate) struct EndpointHealthStore {
+    endpoints: HashMap<String, EndpointHealth>,
+}
+
+impl EndpointHealthStore {
+    pub fn get(&self, endpoint: &str) -> EndpointHealth {
+        self.endpoints.get(endpoint).copied().unwrap_or_default()
+    }
+
+    pub fn record_success(&mut self, endpoint: &str, latency_ms: u32) -> &mut Self {
+        self.endpoints
+            .entry(endpoint.to_string())
+            .or_default()
+            .record_success(latency_ms);
+
+        self
+    }
+
+    pub fn record_failure(&mut self, endpoint: &str, now_ms: u64) -> &mut Self {
+        self.endpoints
+            .entry(endpoint.to_string())
+            .or_default()
+            .record_failure(now_ms);
+
+        self
+    }
+
+    /// The endpoints of `cluster` in the order requests should try them.
+    /// Healthy endpoints keep their configured order and unhealthy ones
+    /// follow, the one that recovers first in front.
+    pub fn ordered_endpoints(&self, cluster: &AdapterCluster, now_ms: u64) -> Vec<String> {
+        let (mut healthy, mut unhealthy): (Vec<_>, Vec<_>) = cluster
+            .endpoints()
+            .iter()
+            .cloned()
+            .partition(|endpoint| self.get(endpoint).is_healthy(now_ms));
+
+        unhealthy.sort_by_key(|endpoint| self.get(endpoint).unhealthy_until_ms);
+        healthy.append(&mut unhealthy);
+
+        healthy
+    }
+
+    /// The endpoint the next request of `cluster` is sent to
+    pub fn serving_endpoint(&self, cluster: &AdapterCluster, now_ms: u64) -> Option<String> {
+        self.ordered_endpoints(cluster, now_ms).into_iter().next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cluster() -> AdapterCluster {
+        AdapterCluster::new()
+            .add_name("local")
+            .add_cluster(Cluster::LocalNet)
+            .add_endpoint("http://localhost:8899")
+            .add_fallback_endpoint("http://localhost:8898")
+            .add_fallback_endpoint("https://rpc.example.com")
+    }
+
+    #[test]
+    fn derives_ws_endpoint() {
+        assert_eq!(cluster().ws_endpoint(), "ws://localhost:8900");
+        assert_eq!(
+            AdapterCluster::new()
+                .add_endpoint("https://api.devnet.solana.com")
+                .ws_endpoint(),
+            "wss://api.devnet.solana.com"
+        );
+        assert_eq!(
+            cluster()
+                .add_ws_endpoint("wss://ws.example.com")
+                .ws_endpoint(),
+            "wss://ws.example.com"
+        );
+    }
+
+    #[test]
+    fn fails_over_to_the_next_healthy_endpoint() {
+        let cluster = cluster();
+        let mut health = EndpointHealthStore::default();
+
+        assert_eq!(
+            health.serving_endpoint(&cluster, 0).as_deref(),
+            Some("http://localhost:8899")
+        );
+
+        health.record_failure("http://localhost:8899", 0);
+        health.record_failure("http://localhost:8898", 100);
+        assert_eq!(
+            health.ordered_endpoints(&cluster, 1_000),
+            vec![
+                "https://rpc.example.com",
+                "http://localhost:8899",
+                "http://localhost:8898"
+            ]
+        );
+
+        // The primary endpoint is tried again once its failure delay passed
+        assert_eq!(
+            health
+                .serving_endpoint(&cluster, UNHEALTHY_DELAY_MS)
+                .as_deref(),
+            Some("http://localhost:8899")
+        );
+    }
+
+    #[test]
+    fn parses_and_masks_headers() {
+        let header = ClusterHeader::parse("Authorization: Bearer 0123456789abcdef").unwrap();
+        assert_eq!(header.name(), "Authorization");
+        assert_eq!(header.value(), "Bearer 0123456789abcdef");
+        assert_eq!(header.display_value(), "••••••••cdef");
+
+        let header = ClusterHeader::parse("x-client:wallet").unwrap();
+        assert!(!header.is_secret());
+        assert_eq!(header.display_value(), "wallet");
+
+        assert!(ClusterHeader::parse("no colon").is_none());
+        assert!(ClusterHeader::parse("bad name: value").is_none());
+        assert!(ClusterHeader::parse("x-empty:").is_none());
+
+        let cluster = cluster()
+            .add_header(ClusterHeader::new("x-api-key", "old"))
+            .add_header(ClusterHeader::new("X-API-KEY", "new"));
+        assert_eq!(cluster.headers(), [ClusterHeader::new("X-API-KEY", "new")]);
+    }
+
+    #[test]
+    fn masks_api_keys_in_endpoints() {
+        assert_eq!(
+            mask_endpoint("https://rpc.example.com/?api-key=0123456789abcdef&commitment=confirmed"),
+            "https://rpc.example.com/?api-key=••••••••cdef&commitment=confirmed"
+        );
+        assert_eq!(
+            mask_endpoint("https://example.solana-mainnet.quiknode.pro/0123456789abcdef0123/"),
+            "https://example.solana-mainnet.quiknode.pro/••••••••0123/"
+        );
+        assert_eq!(
+            mask_endpoint("https://solana-mainnet.g.alchemy.com/v2/Ab3dEf6hIj9kLm2nOp5q"),
+            "https://solana-mainnet.g.alchemy.com/v2/••••••••Op5q"
+        );
+        assert_eq!(
+            mask_endpoint("http://localhost:8899"),
+            "http://localhost:8899"
+        );
+    }
+
+    #[test]
+    fn explorer_links_leave_out_credentials() {
+        assert_eq!(
+            cluster().query_string(),
+            "?cluster=custom&customUrl=http%3A%2F%2Flocalhost%3A8899"
+        );
+        assert_eq!(
+            cluster()
+                .add_endpoint("https://rpc.example.com/?api-key=0123456789abcdef")
+                .query_string(),
+            "?cluster=custom"
+        );
+        assert_eq!(
+            cluster()
+                .add_header(ClusterHeader::new(
+                    "Authorization",
+                    "Bearer 0123456789abcdef"
+                ))
+                .query_string(),
+            "?cluster=custom"
+        );
+    }
+
+    #[test]
+    fn tracks_latency_and_error_rate() {
+        let mut health = EndpointHealth::default();
+        health
+            .record_success(100)
+            .record_success(200)
+            .record_failure(0)
+            .record_success(100);
+
+        assert_eq!(health.requests(), 4);
+        assert_eq!(health.error_rate(), 25);
+        assert_eq!(health.latency_ms(), Some(116));
+        assert!(health.is_healthy(0));
+    }
+}
@@ -1,18 +1,35 @@
 use std::str::FromStr;
 
+use base64::{prelude::BASE64_STANDARD, Engine};
 use dioxus::prelude::*;
 use serde::Deserialize;
 use solana_sdk::{
-    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction::transfer,
-    transaction::Transaction,
+    hash::Hash, instruction::Instruction, pubkey::Pubkey, system_instruction::transfer,
+    transaction::VersionedTransaction,
 };
 use solana_transaction_error::TransactionError;
 use wallet_adapter::{
     web_sys::{js_sys::Date, wasm_bindgen::JsValue},
-    SendOptions, WalletError, WalletResult,
+    WalletError, WalletResult,
 };
 
-use crate::{views::AccountState, FetchReq, ACCOUNT_STATE, CLUSTER_STORAGE, WALLET_ADAPTER};
+use crate::{
+    budgeted_transaction, durable_nonce, memo_instruction, track_rebroadcast, views::AccountState,
+    AccountInfoConfig, CancelHandle, Commitment, CommitmentConfig, Encoding, Lamports,
+    PreparedTransaction, Rebroadcast, RpcBatch, RpcCall, RpcClient, RpcErrorObject, RpcResult,
+    RpcTransport, SendTransactionConfig, SignaturesForAddressConfig, Subscription,
+    TokenAccountsFilter, TokenAmount, TransactionLifetime, ACCOUNT_STATE, CLUSTER_STORAGE, PUBSUB,
+    WALLET_ADAPTER,
+};
+
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// `getMultipleAccounts` accepts at most this many accounts per call
+pub(crate) const MAX_ACCOUNTS_PER_CALL: usize = 100;
+
+/// Signatures loaded per `getSignaturesForAddress` call, the node allows up to 1000
+pub const TRANSACTIONS_PAGE_SIZE: usize = 20;
 
 pub fn format_timestamp(unix_timestamp: i64) -> String {
     let timestamp_ms = unix_timestamp as f64 * 1000.0; //Convert seconds to millisconds
@@ -25,183 +42,270 @@ pub fn format_timestamp(unix_timestamp: i64) -> String {
         .unwrap_or("Invalid Timestamp".to_string())
 }
 
-async fn get_blockhash() -> WalletResult<solana_sdk::hash::Hash> {
-    let options = jzon::object! {
-        "id":1,
-        "jsonrpc":"2.0",
-        "method":"getLatestBlockhash",
-        "params":[
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockHashResponseValue {
+    pub blockhash: String,
+    pub last_valid_block_height: u64,
+}
 
-        ]
+impl BlockHashResponseValue {
+    pub fn hash(&self) -> WalletResult<Hash> {
+        Hash::from_str(&self.blockhash).map_err(|error| WalletError::Op(error.to_string()))
     }
-    .to_string();
+}
 
-    // NOTE: You can use Reqwest crate instead to fetch the blockhash but
-    // this code shows how to use the browser `fetch` api
+pub async fn get_balance(address: &str) -> RpcResult<Lamports> {
+    RpcClient::new()
+        .get_balance(address, CommitmentConfig::default())
+        .await
+        .map(Lamports::new)
+}
 
-    let response_body = FetchReq::new("POST")?
-        .add_header("content-type", "application/json")?
-        .add_header("Accept", "application/json")?
-        .set_body(&options)
-        .send()
-        .await?;
+/// Formats `lamports` as SOL for display
+pub fn format_sol(lamports: u64) -> String {
+    Lamports::new(lamports).to_string()
+}
 
-    let deser = serde_json::from_str::<RpcResponse<ResponseWithContext<BlockHashResponseValue>>>(
-        &response_body,
-    )
-    .unwrap();
+/// Builds a transfer with the latest blockhash, ready to be reviewed
+pub async fn prepare_sol_transfer(
+    recipient: &str,
+    lamports: Lamports,
+    memo: Option<&str>,
+    public_key_bytes: [u8; 32],
+) -> WalletResult<PreparedTransaction> {
+    let client = RpcClient::new();
+    let pubkey = Pubkey::new_from_array(public_key_bytes);
 
-    solana_sdk::hash::Hash::from_str(deser.result.value.blockhash)
-        .map_err(|error| WalletError::Op(error.to_string()))
-}
+    let instructions = [transfer_instruction(pubkey, recipient, lamports)?]
+        .into_iter()
+        .chain(memo.map(|memo| memo_instruction(memo, &pubkey)))
+        .collect::<Vec<Instruction>>();
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct BlockHashResponseValue<'a> {
-    #[serde(borrow)]
-    pub blockhash: &'a str,
-    pub last_valid_block_height: u64,
+    let lifetime = TransactionLifetime::resolve(&client, &pubkey).await?;
+    let tx = budgeted_transaction(&client, &instructions, &pubkey, &[], &lifetime).await?;
+
+    Ok(PreparedTransaction::send(
+        tx,
+        lifetime.last_valid_block_height(),
+        &format!("Send {lamports} SOL"),
+    ))
 }
 
-pub async fn get_balance(address: &str) -> WalletResult<String> {
-    let balance_options = jzon::object! {
-        "id":1,
-        "jsonrpc":"2.0",
-        "method": "getBalance",
-        "params": [
-            address
-        ]
-    }
-    .to_string();
+/// Signs `tx` with the connected wallet and sends it through the node instead
+/// of the wallet. The signed bytes are kept and rebroadcast until the cluster
+/// sees the transaction or the block height passes `last_valid_block_height`.
+pub async fn submit_transaction<T: RpcTransport>(
+    client: &RpcClient<T>,
+    tx: &VersionedTransaction,
+    last_valid_block_height: u64,
+    description: &str,
+) -> WalletResult<String> {
+    let signed_tx = sign_with_wallet(tx).await?;
+    let wire_transaction = BASE64_STANDARD.encode(signed_tx);
 
-    let balance_response = FetchReq::new_for_rpc()?
-        .set_body(&balance_options)
-        .send()
+    // Only the first send runs the preflight checks so
+    // invalid transactions are reported right away
+    let signature = client
+        .send_transaction(
+            &wire_transaction,
+            SendTransactionConfig {
+                max_retries: Some(0),
+                ..Default::default()
+            },
+        )
         .await?;
 
-    let parsed_balance =
-        serde_json::from_str::<RpcResponse<ResponseWithContext<u64>>>(&balance_response)
-            .map_err(|error| WalletError::Op(error.to_string()))?;
+    let mut rebroadcast = Rebroadcast::new(&wire_transaction, last_valid_block_height);
+    if let Some((nonce_account, nonce)) = durable_nonce(&tx.message) {
+        rebroadcast = rebroadcast.add_durable_nonce(nonce_account, nonce);
+    }
+
+    track_rebroadcast(&signature, description, rebroadcast);
+
+    // Refresh the account once the transaction confirms
+    if let Some(pubsub) = PUBSUB.read().as_ref() {
+        pubsub.subscribe(Subscription::Signature(signature.clone()));
+    }
 
-    // WARNING: Do better financial math here
-    Ok((parsed_balance.result.value as f64 / LAMPORTS_PER_SOL as f64).to_string())
+    Ok(signature)
 }
 
-pub async fn send_sol_req(
-    recipient: &str,
-    lamports: u64,
-    public_key_bytes: [u8; 32],
-) -> WalletResult<()> {
+/// Asks the connected wallet to sign `tx`, returns the signed wire transaction
+pub async fn sign_with_wallet(tx: &VersionedTransaction) -> WalletResult<Vec<u8>> {
     let cluster = CLUSTER_STORAGE.read().active_cluster().cluster();
 
-    let pubkey = Pubkey::new_from_array(public_key_bytes);
+    let tx_bytes = bincode::serialize(tx).map_err(|error| WalletError::Op(error.to_string()))?;
+
+    WALLET_ADAPTER
+        .read()
+        .sign_transaction(&tx_bytes, Some(cluster))
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(WalletError::Op(
+            "The wallet did not return the signed transaction".to_string(),
+        ))
+}
+
+/// Transfers `lamports` from `payer` to `recipient`
+pub fn transfer_instruction(
+    payer: Pubkey,
+    recipient: &str,
+    lamports: Lamports,
+) -> WalletResult<Instruction> {
     let recipient = Pubkey::from_str(recipient).or(Err(WalletError::Op(
         "Invalid Recipient Address".to_string(),
     )))?;
 
-    let send_sol_instruction = transfer(&pubkey, &recipient, lamports);
-    let mut tx = Transaction::new_with_payer(&[send_sol_instruction], Some(&pubkey));
-    let blockhash = get_blockhash().await?;
+    Ok(transfer(&payer, &recipient, lamports.get()))
+}
 
-    tx.message.recent_blockhash = blockhash;
-    let tx_bytes = bincode::serialize(&tx).map_err(|error| WalletError::Op(error.to_string()))?;
+/// Returns the signature of the airdrop transaction
+pub async fn request_airdrop(lamports: Lamports, address: &str) -> RpcResult<String> {
+    RpcClient::new()
+        .request_airdrop(address, lamports.get(), CommitmentConfig::default())
+        .await
+}
 
-    WALLET_ADAPTER
-        .read()
-        .sign_and_send_transaction(&tx_bytes, cluster, SendOptions::default())
-        .await?;
+/// `cancel_handle` aborts the requests, eg. when the view showing them is closed
+pub async fn accounts_runner(
+    address: &str,
+    cancel_handle: &CancelHandle,
+) -> RpcResult<AccountState> {
+    *ACCOUNT_STATE.write() = AccountState::default();
 
-    Ok(())
+    load_account_state(
+        &RpcClient::with_cancel_handle(cancel_handle.clone()),
+        address,
+    )
+    .await
 }
 
-pub async fn request_airdrop(lamports: u64, address: &str) -> WalletResult<()> {
-    let options = jzon::object! {
-        "id":1,
-        "jsonrpc":"2.0",
-        "method": "requestAirdrop",
-        "params": [
-            address,
-            lamports
-        ]
-    }
-    .to_string();
+/// Loads the balance, token accounts and signatures of `address`
+/// in a single batch request
+pub async fn load_account_state<T: RpcTransport>(
+    client: &RpcClient<T>,
+    address: &str,
+) -> RpcResult<AccountState> {
+    let mut batch = RpcBatch::new();
 
-    let response = FetchReq::new_for_rpc()?.set_body(&options).send().await?;
+    let balance = batch.add(RpcCall::get_balance(address, CommitmentConfig::default()));
+    let token_accounts = batch.add(RpcCall::get_token_accounts_by_owner(
+        address,
+        TokenAccountsFilter::ProgramId(TOKEN_PROGRAM_ID.to_string()),
+        AccountInfoConfig::default(),
+    ));
+    let token_2022_accounts = batch.add(RpcCall::get_token_accounts_by_owner(
+        address,
+        TokenAccountsFilter::ProgramId(TOKEN_2022_PROGRAM_ID.to_string()),
+        AccountInfoConfig::default(),
+    ));
+    let signatures = batch.add(RpcCall::get_signatures_for_address(
+        address,
+        transactions_page_config(None),
+    ));
 
-    serde_json::from_str::<RpcResponse<String>>(&response)
-        .map_err(|error| WalletError::Op(error.to_string()))?;
+    let mut responses = client.send_batch(batch).await?;
 
-    Ok(())
-}
+    let mut token_accounts = responses.take(token_accounts)?.value;
+    token_accounts.extend(responses.take(token_2022_accounts)?.value);
 
-pub async fn accounts_runner(address: &str) -> WalletResult<AccountState> {
-    *ACCOUNT_STATE.write() = AccountState::default();
+    // Mint extensions only decorate the cards so the
+    // accounts are still listed if the mints fail to load
+    load_mint_extensions(client, &mut token_accounts).await.ok();
 
-    let balance = crate::get_balance(&address).await?;
+    let mut account_state = AccountState {
+        balance: Lamports::new(responses.take(balance)?.value),
+        token_accounts,
+        ..Default::default()
+    };
+    account_state.add_transactions_page(responses.take(signatures)?);
 
-    let token_accounts_options = jzon::object! {
-        "id":1,
-        "jsonrpc":"2.0",
-        "method": "getTokenAccountsByOwner",
-        "params": [
-            address,
-            {
-                "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
-            },
-            {
-                "encoding": "jsonParsed"
-            }
-        ]
-    }
-    .to_string();
+    Ok(account_state)
+}
 
-    let fetched_token_accounts = FetchReq::new_for_rpc()?
-        .set_body(&token_accounts_options)
-        .send()
-        .await?;
+/// Fills [TokenAccountResponse::mint_extensions] of the Token-2022 accounts,
+/// extensions like transfer fees are stored in the mint and not the account
+pub async fn load_mint_extensions<T: RpcTransport>(
+    client: &RpcClient<T>,
+    token_accounts: &mut [TokenAccountResponse],
+) -> RpcResult<()> {
+    let mut mints = token_accounts
+        .iter()
+        .filter(|token_account| token_account.program() == TokenProgram::Token2022)
+        .map(|token_account| token_account.mint())
+        .collect::<Vec<String>>();
+    mints.sort();
+    mints.dedup();
 
-    let parsed_token_accounts = serde_json::from_str::<
-        RpcResponse<ResponseWithContext<Vec<TokenAccountResponse>>>,
-    >(&fetched_token_accounts)
-    .map_err(|error| WalletError::Op(error.to_string()))?;
-
-    let token_accounts = parsed_token_accounts.result.value;
-
-    let get_signatures_options = jzon::object! {
-        "jsonrpc": "2.0",
-        "id": 1,
-        "method": "getSignaturesForAddress",
-        "params": [
-          address
-        ]
-    }
-    .to_string();
-    let fetched_signatures = FetchReq::new("POST")?
-        .add_header("Content-Type", "application/json")?
-        // .add_header("Accept", "application/json")?
-        .set_body(&get_signatures_options)
-        .send()
-        .await?;
+    for mints in mints.chunks(MAX_ACCOUNTS_PER_CALL) {
+        let mint_accounts = client
+            .get_multiple_accounts::<MintAccount>(mints, AccountInfoConfig::default())
+            .await?;
+
+        mints
+            .iter()
+            .zip(mint_accounts)
+            .for_each(|(mint, mint_account)| {
+                let extensions = mint_account
+                    .map(|mint_account| mint_account.data.parsed.info.extensions)
+                    .unwrap_or_default();
 
-    let parsed_signatures_response =
-        serde_json::from_str::<RpcResponse<Vec<SignaturesResponse>>>(&fetched_signatures)
-            .map_err(|error| WalletError::Op(error.to_string()))?;
+                token_accounts
+                    .iter_mut()
+                    .filter(|token_account| token_account.account.data.parsed.info.mint == *mint)
+                    .for_each(|token_account| token_account.mint_extensions = extensions.clone());
+            });
+    }
 
-    let signatures = parsed_signatures_response.result;
+    Ok(())
+}
 
-    Ok(AccountState {
-        balance,
-        token_accounts,
-        transactions: signatures.clone(),
-    })
+/// Loads the page of signatures of `address` older than `before`
+pub async fn load_transactions_page<T: RpcTransport>(
+    client: &RpcClient<T>,
+    address: &str,
+    before: &str,
+) -> RpcResult<Vec<SignaturesResponse>> {
+    client
+        .get_signatures_for_address(address, transactions_page_config(Some(before)))
+        .await
+}
+
+fn transactions_page_config(before: Option<&str>) -> SignaturesForAddressConfig {
+    SignaturesForAddressConfig {
+        limit: Some(TRANSACTIONS_PAGE_SIZE),
+        before: before.map(|signature| signature.to_string()),
+        ..Default::default()
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcResponse<T> {
     pub jsonrpc: String,
-    pub id: u8,
-    pub result: T,
+    /// `null` when the node could not read the id from the request
+    pub id: Option<u64>,
+    #[serde(flatten)]
+    pub payload: RpcPayload<T>,
+}
+
+impl<T> RpcResponse<T> {
+    pub fn into_result(self) -> RpcResult<T> {
+        match self.payload {
+            RpcPayload::Result(value) => Ok(value),
+            RpcPayload::Error(error) => Err(error.into()),
+        }
+    }
+}
+
+/// A JSON-RPC response carries either a `result` or an `error`
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcPayload<T> {
+    Result(T),
+    Error(RpcErrorObject),
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize)]
@@ -210,20 +314,80 @@ pub struct SignaturesResponse {
     pub block_time: Option<i64>,
     pub confirmation_status: Option<String>,
     pub err: Option<TransactionError>,
+    /// The memos of the transaction as formatted by the node, see [Self::memos]
+    #[serde(default)]
+    pub memo: Option<String>,
     pub signature: String,
 }
 
+impl SignaturesResponse {
+    /// The node prefixes each memo with its length in bytes and joins them
+    /// with `; `, eg. `[13] deposit-48213; [8] tip; bot`. The length tells
+    /// where a memo containing `; ` ends.
+    pub fn memos(&self) -> Vec<String> {
+        let Some(mut remaining) = self.memo.as_deref() else {
+            return Vec::new();
+        };
+        let mut memos = Vec::<String>::new();
+
+        while !remaining.is_empty() {
+            let memo = remaining
+                .strip_prefix('[')
+                .and_then(|rest| rest.split_once("] "))
+                .and_then(|(length, rest)| {
+                    let length = length.parse::<usize>().ok()?;
+
+                    Some((rest.get(..length)?, rest.get(length..)?))
+                });
+
+            // Not formatted by the node, shown as is
+            let Some((memo, rest)) = memo else {
+                memos.push(remaining.to_string());
+                break;
+            };
+
+            memos.push(memo.to_string());
+            remaining = rest.strip_prefix("; ").unwrap_or(rest);
+        }
+
+        memos
+    }
+}
+
+/// An entry of `getSignatureStatuses`, the node answers `null` instead
+/// for signatures it has not seen
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureStatus {
+    pub slot: u64,
+    /// `None` once the transaction is rooted
+    pub confirmations: Option<usize>,
+    pub err: Option<TransactionError>,
+    pub confirmation_status: Option<Commitment>,
+}
+
 #[derive(Debug, PartialEq, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResponseWithContext<O> {
+    #[serde(default)]
+    pub context: RpcContext,
     pub value: O,
 }
 
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContext {
+    pub slot: u64,
+}
+
 #[derive(Debug, PartialEq, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenAccountResponse {
     pub pubkey: String,
     pub account: Account,
+    /// The extensions of the mint, loaded separately by [load_mint_extensions]
+    #[serde(skip)]
+    pub mint_extensions: Vec<TokenExtension>,
 }
 
 impl TokenAccountResponse {
@@ -235,6 +399,10 @@ impl TokenAccountResponse {
         self.pubkey.to_owned()
     }
 
+    pub fn owner(&self) -> String {
+        self.account.data.parsed.info.owner.to_owned()
+    }
+
     pub fn balance(&self) -> String {
         self.account
             .data
@@ -245,23 +413,114 @@ impl TokenAccountResponse {
             .to_owned()
     }
 
+    /// The exact balance, [TokenAccountResponse::balance] also
+    /// applies the UI multipliers of Token-2022 extensions
+    pub fn amount(&self) -> TokenAmount {
+        let token_amount = &self.account.data.parsed.info.token_amount;
+
+        TokenAmount::new(
+            token_amount.amount.parse().unwrap_or_default(),
+            token_amount.decimals,
+        )
+    }
+
     pub fn state(&self) -> String {
         self.account.data.parsed.info.state.to_uppercase()
     }
+
+    pub fn lamports(&self) -> Lamports {
+        Lamports::new(self.account.lamports)
+    }
+
+    /// Only empty accounts can be closed. Frozen accounts and Token-2022
+    /// accounts holding withheld transfer fees are rejected by the program.
+    pub fn is_closable(&self) -> bool {
+        let has_withheld_fees = self
+            .account
+            .data
+            .parsed
+            .info
+            .extensions
+            .iter()
+            .any(|extension| {
+                matches!(
+                    extension,
+                    TokenExtension::TransferFeeAmount {
+                        withheld_amount: 1..
+                    }
+                )
+            });
+
+        self.amount().is_zero() && self.state() != "FROZEN" && !has_withheld_fees
+    }
+
+    pub fn program(&self) -> TokenProgram {
+        if self.account.owner == TOKEN_2022_PROGRAM_ID {
+            TokenProgram::Token2022
+        } else {
+            TokenProgram::Token
+        }
+    }
+
+    /// The extensions of the token account followed by those of its mint
+    pub fn extensions(&self) -> Vec<TokenExtension> {
+        self.account
+            .data
+            .parsed
+            .info
+            .extensions
+            .iter()
+            .chain(self.mint_extensions.iter())
+            .cloned()
+            .collect()
+    }
+}
+
+/// The program owning a token account
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum TokenProgram {
+    #[default]
+    Token,
+    Token2022,
+}
+
+impl TokenProgram {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Token => TOKEN_PROGRAM_ID,
+            Self::Token2022 => TOKEN_2022_PROGRAM_ID,
+        }
+    }
+}
+
+impl core::fmt::Display for TokenProgram {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Token => write!(f, "SPL TOKEN"),
+            Self::Token2022 => write!(f, "TOKEN-2022"),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Account {
     pub data: TokenData,
+    /// The rent deposit, returned when the account is closed
+    #[serde(default)]
+    pub lamports: u64,
+    /// The token program the account belongs to
+    #[serde(default)]
+    pub owner: String,
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct TokenAmount {
+pub struct UiTokenAmount {
     pub amount: String,
     pub decimals: u8,
-    pub ui_amount: f64,
+    /// `null` for empty accounts in transaction token balances
+    pub ui_amount: Option<f64>,
     pub ui_amount_string: String,
 }
 
@@ -269,8 +528,13 @@ pub struct TokenAmount {
 #[serde(rename_all = "camelCase")]
 pub struct ParseInfo {
     pub mint: String,
+    /// The wallet allowed to transfer from the account
+    pub owner: String,
     pub state: String,
-    pub token_amount: TokenAmount,
+    pub token_amount: UiTokenAmount,
+    /// Only Token-2022 accounts have extensions
+    #[serde(default, deserialize_with = "deserialize_extensions")]
+    pub extensions: Vec<TokenExtension>,
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize)]
@@ -284,3 +548,512 @@ pub struct Parsed {
 pub struct TokenData {
     pub parsed: Parsed,
 }
+
+/// An account fetched with the `base64` encoding
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodedAccount {
+    /// The encoded data and its encoding
+    pub data: (String, Encoding),
+    pub owner: String,
+    pub lamports: u64,
+}
+
+impl EncodedAccount {
+    /// `None` unless the account was fetched with [Encoding::Base64]
+    pub fn decoded_data(&self) -> Option<Vec<u8>> {
+        match self.data {
+            (ref data, Encoding::Base64) => BASE64_STANDARD.decode(data).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// A `jsonParsed` mint account as returned by `getMultipleAccounts`
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MintAccount {
+    pub data: MintData,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MintData {
+    pub parsed: ParsedMint,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedMint {
+    pub info: MintInfo,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MintInfo {
+    pub decimals: u8,
+    #[serde(default, deserialize_with = "deserialize_extensions")]
+    pub extensions: Vec<TokenExtension>,
+}
+
+/// A Token-2022 extension of a mint or token account. Only the fields shown
+/// in the UI are kept.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(tag = "extension", content = "state", rename_all = "camelCase")]
+pub enum TokenExtension {
+    // Mint extensions
+    #[serde(rename_all = "camelCase")]
+    TransferFeeConfig {
+        #[serde(default)]
+        newer_transfer_fee: TransferFee,
+        #[serde(default)]
+        withheld_amount: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    InterestBearingConfig {
+        /// The yearly rate in basis points
+        #[serde(default)]
+        current_rate: i16,
+    },
+    NonTransferable,
+    #[serde(rename_all = "camelCase")]
+    MetadataPointer {
+        #[serde(default)]
+        metadata_address: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    TokenMetadata {
+        #[serde(default)]
+        name: String,
+        #[serde(default)]
+        symbol: String,
+        #[serde(default)]
+        uri: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    MintCloseAuthority {
+        #[serde(default)]
+        close_authority: Option<String>,
+    },
+    PermanentDelegate {
+        #[serde(default)]
+        delegate: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    DefaultAccountState {
+        #[serde(default)]
+        account_state: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    TransferHook {
+        #[serde(default)]
+        program_id: Option<String>,
+    },
+    ConfidentialTransferMint {},
+    GroupPointer {},
+    GroupMemberPointer {},
+    TokenGroup {},
+    TokenGroupMember {},
+    // Token account extensions
+    ImmutableOwner,
+    #[serde(rename_all = "camelCase")]
+    MemoTransfer {
+        #[serde(default)]
+        require_incoming_transfer_memos: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    CpiGuard {
+        #[serde(default)]
+        lock_cpi: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    TransferFeeAmount {
+        #[serde(default)]
+        withheld_amount: u64,
+    },
+    NonTransferableAccount,
+    TransferHookAccount {},
+    ConfidentialTransferAccount {},
+    /// Set by [deserialize_extensions] for anything it cannot parse
+    #[serde(skip)]
+    Unknown,
+}
+
+/// Parses every extension on its own so a new extension or a changed
+/// layout turns into [TokenExtension::Unknown] instead of failing the account
+fn deserialize_extensions<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<TokenExtension>, D::Error> {
+    let extensions = Vec::<serde_json::Value>::deserialize(deserializer)?
+        .into_iter()
+        .map(|extension| serde_json::from_value(extension).unwrap_or(TokenExtension::Unknown))
+        .collect();
+
+    Ok(extensions)
+}
+
+/// The fee charged on every transfer of a mint with [TokenExtension::TransferFeeConfig]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferFee {
+    pub epoch: u64,
+    /// In base units of the token
+    pub maximum_fee: u64,
+    pub transfer_fee_basis_points: u16,
+}
+
+impl TokenExtension {
+    /// Extensions that only confirm the default behaviour are left out of the UI
+    pub fn is_notable(&self) -> bool {
+        !matches!(
+            self,
+            Self::ImmutableOwner
+                | Self::Unknown
+                | Self::MemoTransfer {
+                    require_incoming_transfer_memos: false
+                }
+                | Self::CpiGuard { lock_cpi: false }
+                | Self::TransferFeeAmount { withheld_amount: 0 }
+        )
+    }
+}
+
+impl core::fmt::Display for TokenExtension {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TransferFeeConfig {
+                newer_transfer_fee, ..
+            } => write!(
+                f,
+                "TRANSFER FEE {}.{:02}%",
+                newer_transfer_fee.transfer_fee_basis_points / 100,
+                newer_transfer_fee.transfer_fee_basis_points % 100
+            ),
+            Self::InterestBearingConfig { current_rate } => write!(
+                f,
+                "INTEREST {}{}.{:02}%",
+                if *current_rate < 0 { "-" } else { "" },
+                current_rate.unsigned_abs() / 100,
+                current_rate.unsigned_abs() % 100
+            ),
+            Self::NonTransferable | Self::NonTransferableAccount => write!(f, "NON-TRANSFERABLE"),
+            Self::MetadataPointer { .. } => write!(f, "METADATA POINTER"),
+            Self::TokenMetadata { symbol, .. } if !symbol.is_empty() => {
+                write!(f, "METADATA {symbol}")
+            }
+            Self::TokenMetadata { .. } => write!(f, "METADATA"),
+            Self::MintCloseAuthority { .. } => write!(f, "CLOSABLE MINT"),
+            Self::PermanentDelegate { .. } => write!(f, "PERMANENT DELEGATE"),
+            Self::DefaultAccountState { account_state } => {
+                write!(f, "DEFAULT {}", account_state.to_uppercase())
+            }
+            Self::TransferHook { .. } | Self::TransferHookAccount {} => write!(f, "TRANSFER HOOK"),
+            Self::ConfidentialTransferMint {} | Self::ConfidentialTransferAccount {} => {
+                write!(f, "CONFIDENTIAL TRANSFERS")
+            }
+            Self::GroupPointer {} | Self::TokenGroup {} => write!(f, "TOKEN GROUP"),
+            Self::GroupMemberPointer {} | Self::TokenGroupMember {} => write!(f, "GROUP MEMBER"),
+            Self::ImmutableOwner => write!(f, "IMMUTABLE OWNER"),
+            Self::MemoTransfer { .. } => write!(f, "MEMO REQUIRED"),
+            Self::CpiGuard { .. } => write!(f, "CPI GUARD"),
+            Self::TransferFeeAmount { withheld_amount } => {
+                write!(f, "WITHHELD FEES {withheld_amount}")
+            }
+            Self::Unknown => write!(f, "UNKNOWN EXTENSION"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{block_on, Commitment, MockTransport, RpcError, TransactionProgress, OWNER};
+
+    const UNHEALTHY_OWNER: &str = "Hx6LbkMHe69DYawhPyVNs8Apa6tyfogfzQV6a7XkwBUU";
+    const FIXTURES: &str = include_str!("../fixtures/rpc/accounts.json");
+
+    fn client() -> RpcClient<MockTransport> {
+        RpcClient::with_transport(MockTransport::from_json(FIXTURES).unwrap())
+    }
+
+    #[test]
+    fn loads_account_state() {
+        let state = block_on(load_account_state(&client(), OWNER)).unwrap();
+
+        assert_eq!(state.balance, Lamports::new(1_250_000_000));
+        assert_eq!(state.token_accounts().len(), 3);
+        assert_eq!(state.transactions().len(), 3);
+    }
+
+    #[test]
+    fn paginates_transactions() {
+        // Nothing to load before the first page came in
+        assert!(!AccountState::default().has_more_transactions());
+
+        let mut state = block_on(load_account_state(&client(), OWNER)).unwrap();
+
+        // The first page is shorter than a full page so there is nothing older
+        assert!(!state.has_more_transactions());
+        assert_eq!(
+            state.history_cursor.as_deref(),
+            Some(state.transactions()[2].signature.as_str())
+        );
+
+        let page = block_on(load_transactions_page(
+            &client(),
+            OWNER,
+            state.history_cursor.as_deref().unwrap(),
+        ))
+        .unwrap();
+        assert!(page.is_empty());
+
+        let full_page = vec![state.transactions()[0].clone(); TRANSACTIONS_PAGE_SIZE];
+        state.add_transactions_page(full_page);
+        assert!(state.has_more_transactions());
+        assert_eq!(state.transactions().len(), 3 + TRANSACTIONS_PAGE_SIZE);
+    }
+
+    #[test]
+    fn merges_reloads_without_losing_older_pages() {
+        let latest = block_on(load_account_state(&client(), OWNER)).unwrap();
+
+        // The two older signatures were loaded with a further page
+        let mut state = AccountState::default();
+        state.add_transactions_page(latest.transactions()[1..].to_vec());
+        state.add_transactions_page(vec![
+            latest.transactions()[2].clone();
+            TRANSACTIONS_PAGE_SIZE
+        ]);
+        let cursor = state.history_cursor.clone();
+
+        state.merge_latest(block_on(load_account_state(&client(), OWNER)).unwrap());
+
+        assert_eq!(state.balance, latest.balance);
+        assert_eq!(state.token_accounts(), latest.token_accounts());
+        assert_eq!(state.transactions().len(), 3 + TRANSACTIONS_PAGE_SIZE);
+        assert_eq!(state.transactions()[..3], latest.transactions()[..]);
+        assert_eq!(state.history_cursor, cursor);
+        assert!(state.has_more_transactions());
+
+        // Without an overlap the older pages could leave a gap
+        let mut state = AccountState::default();
+        state.add_transactions_page(vec![latest.transactions()[0].clone()]);
+        state.transactions[0].signature = "unknown".to_string();
+        state.merge_latest(block_on(load_account_state(&client(), OWNER)).unwrap());
+
+        assert_eq!(state, latest);
+    }
+
+    #[test]
+    fn falls_back_to_sequential_calls_when_batches_are_rejected() {
+        let transport = MockTransport::from_json(FIXTURES).unwrap().reject_batches();
+        let client = RpcClient::with_transport(transport);
+
+        let state = block_on(load_account_state(&client, OWNER)).unwrap();
+
+        assert_eq!(
+            state,
+            block_on(load_account_state(&self::client(), OWNER)).unwrap()
+        );
+    }
+
+    #[test]
+    fn token_account_accessors() {
+        let state = block_on(load_account_state(&client(), OWNER)).unwrap();
+        let usdc = &state.token_accounts()[0];
+
+        assert_eq!(usdc.mint(), "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+        assert_eq!(
+            usdc.ata_address(),
+            "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T"
+        );
+        assert_eq!(usdc.balance(), "12.5");
+        assert_eq!(usdc.amount(), TokenAmount::new(12_500_000, 6));
+        assert_eq!(usdc.state(), "INITIALIZED");
+    }
+
+    #[test]
+    fn zero_decimal_token() {
+        let state = block_on(load_account_state(&client(), OWNER)).unwrap();
+        let token_account = &state.token_accounts()[1];
+
+        assert_eq!(token_account.balance(), "5");
+        assert_eq!(token_account.amount().to_string(), "5");
+        assert_eq!(token_account.state(), "FROZEN");
+        assert_eq!(
+            token_account.account.data.parsed.info.token_amount.decimals,
+            0
+        );
+    }
+
+    #[test]
+    fn token_2022_extensions() {
+        let state = block_on(load_account_state(&client(), OWNER)).unwrap();
+        let token_account = &state.token_accounts()[2];
+
+        assert_eq!(state.token_accounts()[0].program(), TokenProgram::Token);
+        assert_eq!(token_account.program(), TokenProgram::Token2022);
+        assert_eq!(token_account.balance(), "2");
+
+        let extensions = token_account.extensions();
+        assert_eq!(
+            extensions,
+            vec![
+                TokenExtension::ImmutableOwner,
+                TokenExtension::TransferFeeAmount { withheld_amount: 0 },
+                TokenExtension::Unknown,
+                TokenExtension::TransferFeeConfig {
+                    newer_transfer_fee: TransferFee {
+                        epoch: 605,
+                        maximum_fee: 5_000_000,
+                        transfer_fee_basis_points: 50,
+                    },
+                    withheld_amount: 0,
+                },
+                TokenExtension::InterestBearingConfig { current_rate: 525 },
+                TokenExtension::MetadataPointer {
+                    metadata_address: Some(
+                        "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo".to_string()
+                    ),
+                },
+                TokenExtension::TokenMetadata {
+                    name: "PayPal USD".to_string(),
+                    symbol: "PYUSD".to_string(),
+                    uri: "https://token-metadata.paxos.com/pyusd_metadata/prod/solana/pyusd_metadata.json"
+                        .to_string(),
+                },
+                TokenExtension::Unknown,
+            ]
+        );
+
+        let labels = extensions
+            .iter()
+            .filter(|extension| extension.is_notable())
+            .map(|extension| extension.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                "TRANSFER FEE 0.50%",
+                "INTEREST 5.25%",
+                "METADATA POINTER",
+                "METADATA PYUSD"
+            ]
+        );
+    }
+
+    #[test]
+    fn signatures_without_block_time() {
+        let state = block_on(load_account_state(&client(), OWNER)).unwrap();
+
+        // `blockTime` is `null` in the first entry and missing in the last
+        assert_eq!(state.transactions()[0].block_time, None);
+        assert_eq!(state.transactions()[1].block_time, Some(1735689600));
+        assert_eq!(state.transactions()[2].block_time, None);
+    }
+
+    #[test]
+    fn signature_memos() {
+        let state = block_on(load_account_state(&client(), OWNER)).unwrap();
+
+        assert!(state.transactions()[0].memos().is_empty());
+        // The second memo contains the separator
+        assert_eq!(
+            state.transactions()[2].memos(),
+            ["deposit-48213", "tip; bot"]
+        );
+
+        let unformatted = SignaturesResponse {
+            memo: Some("deposit-48213".to_string()),
+            ..state.transactions()[2].clone()
+        };
+        assert_eq!(unformatted.memos(), ["deposit-48213"]);
+    }
+
+    #[test]
+    fn failed_transaction() {
+        let state = block_on(load_account_state(&client(), OWNER)).unwrap();
+
+        assert!(state.transactions()[0].err.is_none());
+        assert!(matches!(
+            state.transactions()[1].err,
+            Some(TransactionError::InstructionError(0, _))
+        ));
+    }
+
+    #[test]
+    fn signature_statuses() {
+        let state = block_on(load_account_state(&client(), OWNER)).unwrap();
+        let signatures = state
+            .transactions()
+            .iter()
+            .map(|tx| tx.signature.clone())
+            .collect::<Vec<_>>();
+
+        let statuses = block_on(client().get_signature_statuses(&signatures, false)).unwrap();
+        let progress = statuses
+            .iter()
+            .map(|status| status.as_ref().map(TransactionProgress::from))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            progress,
+            vec![
+                Some(TransactionProgress::Finalized),
+                Some(TransactionProgress::Failed(
+                    TransactionError::InstructionError(
+                        0,
+                        solana_sdk::instruction::InstructionError::Custom(1)
+                    )
+                )),
+                None,
+            ]
+        );
+        assert_eq!(
+            statuses[1].as_ref().unwrap().confirmation_status,
+            Some(Commitment::Confirmed)
+        );
+    }
+
+    #[test]
+    fn rebroadcast_until_expired() {
+        let client = client();
+
+        // Rebroadcasts skip preflight and leave retrying to the app
+        let signature = block_on(client.send_transaction(
+            "AQID",
+            SendTransactionConfig {
+                skip_preflight: Some(true),
+                max_retries: Some(0),
+                ..Default::default()
+            },
+        ))
+        .unwrap();
+        assert!(signature.starts_with("5h6xBEau"));
+
+        let block_height = block_on(client.get_block_height(CommitmentConfig::default())).unwrap();
+        assert!(!Rebroadcast::new("AQID", block_height).is_expired(block_height));
+        assert!(Rebroadcast::new("AQID", block_height - 1).is_expired(block_height));
+    }
+
+    #[test]
+    fn node_error_is_surfaced() {
+        let balance = block_on(client().get_balance(UNHEALTHY_OWNER, CommitmentConfig::default()));
+
+        assert_eq!(
+            balance,
+            Err(RpcError::NodeUnhealthy {
+                message: "Node is behind by 42 slots".to_string(),
+                num_slots_behind: Some(42),
+            })
+        );
+    }
+
+    #[test]
+    fn missing_fixture() {
+        let balance =
+            block_on(client().get_balance(OWNER, CommitmentConfig::new(Commitment::Processed)));
+
+        assert!(matches!(balance, Err(RpcError::Transport(_))));
+    }
+}
@@ -1,20 +1,80 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
 use dioxus::prelude::*;
+use gloo_timers::callback::Timeout;
 use wallet_adapter::{
     wasm_bindgen_futures::JsFuture,
-    web_sys::{wasm_bindgen::JsCast, Headers, Request, RequestInit, Response},
+    web_sys::{
+        js_sys::Date, wasm_bindgen::JsCast, AbortController, Headers, Request, RequestInit,
+        Response,
+    },
     WalletError, WalletResult,
 };
 
 use crate::{
-    views::ClusterNetState, CLUSTER_NET_STATE, CLUSTER_STORAGE, GLOBAL_MESSAGE, WALLET_ADAPTER,
+    views::ClusterNetState, RpcError, RpcRequest, RpcResult, RpcTransport, CLUSTER_NET_STATE,
+    CLUSTER_STORAGE, ENDPOINT_HEALTH, GLOBAL_MESSAGE, WALLET_ADAPTER,
 };
 
+/// How long a single endpoint gets to answer before the request is aborted
+pub const DEFAULT_TIMEOUT_MS: u32 = 15_000;
+
+/// Aborts the in-flight requests sent with it. Components hold one and
+/// cancel it when they are dropped so leaving a view does not keep
+/// its requests running. Requests sent after [CancelHandle::cancel]
+/// are not affected so the same handle can be used again.
+#[derive(Debug, Default, Clone)]
+pub struct CancelHandle {
+    in_flight: Rc<RefCell<Vec<AbortController>>>,
+}
+
+impl CancelHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.in_flight
+            .borrow_mut()
+            .drain(..)
+            .for_each(|controller| controller.abort());
+    }
+
+    /// The number of requests that can still be cancelled
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.borrow().len()
+    }
+
+    fn register(&self, controller: &AbortController) {
+        self.in_flight.borrow_mut().push(controller.clone());
+    }
+
+    fn unregister(&self, controller: &AbortController) {
+        self.in_flight
+            .borrow_mut()
+            .retain(|current| current != controller);
+    }
+}
+
+impl PartialEq for CancelHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.in_flight, &other.in_flight)
+    }
+}
+
+impl Eq for CancelHandle {}
+
 // NOTE: You can use Reqwest crate instead to fetch the blockhash but
 // this code shows how to use the browser `fetch` api
 #[derive(Debug)]
 pub struct FetchReq {
     headers: Headers,
     options: RequestInit,
+    timeout_ms: u32,
+    cancel_handle: Option<CancelHandle>,
 }
 
 impl FetchReq {
@@ -25,6 +85,8 @@ impl FetchReq {
         Ok(Self {
             headers: Headers::new()?,
             options,
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+            cancel_handle: None,
         })
     }
 
@@ -37,18 +99,15 @@ impl FetchReq {
     pub async fn ping() {
         *CLUSTER_NET_STATE.write() = ClusterNetState::Waiting;
 
-        let body = jzon::object! {
-            jsonrpc: "2.0",
-            id: 1,
-            method: "getVersion",
-        }
-        .to_string();
+        let request = RpcRequest::new("getVersion", serde_json::json!([]))
+            .to_json()
+            .map_err(WalletError::from)
+            .and_then(|body| Ok(Self::new_for_rpc()?.set_body(&body)));
 
-        match Self::new_for_rpc() {
+        match request {
+            // `send` sets the network state of the cluster
             Ok(req) => {
-                if req.set_body(&body).build().await.is_err() {
-                    *CLUSTER_NET_STATE.write() = ClusterNetState::Failure;
-                }
+                req.send().await.ok();
             }
             Err(error) => {
                 GLOBAL_MESSAGE
@@ -70,40 +129,197 @@ impl FetchReq {
         self
     }
 
-    pub async fn send(self) -> WalletResult<String> {
-        let resp = self.build().await?;
+    /// Aborts the request with [RpcError::Timeout] if an endpoint
+    /// takes longer than `timeout_ms` to send the whole response
+    pub fn set_timeout(mut self, timeout_ms: u32) -> Self {
+        self.timeout_ms = timeout_ms;
 
-        JsFuture::from(resp.text()?)
+        self
+    }
+
+    /// Lets `cancel_handle` abort the request with [RpcError::Cancelled]
+    pub fn add_cancel_handle(mut self, cancel_handle: CancelHandle) -> Self {
+        self.cancel_handle.replace(cancel_handle);
+
+        self
+    }
+
+    /// Sends the request to the active cluster and returns the response body.
+    /// Endpoints are tried in the order of
+    /// [EndpointHealthStore::ordered_endpoints](crate::EndpointHealthStore::ordered_endpoints)
+    /// and the next one is used on network errors, timeouts, HTTP 429 and 5xx.
+    pub async fn send(self) -> RpcResult<String> {
+        let active_cluster = CLUSTER_STORAGE.read().active_cluster().clone();
+
+        // Provider credentials and any other header the cluster needs
+        for header in active_cluster.headers() {
+            self.headers
+                .set(header.name(), header.value())
+                .map_err(WalletError::from)?;
+        }
+        self.options.set_headers(&self.headers);
+
+        let endpoints = ENDPOINT_HEALTH
+            .peek()
+            .ordered_endpoints(&active_cluster, now_ms());
+
+        let mut last_error = RpcError::Transport(String::from("The cluster has no endpoints"));
+        let mut last_response = Option::<String>::None;
+
+        for endpoint in endpoints {
+            let started_ms = now_ms();
+
+            match self.fetch(&endpoint).await {
+                Ok((status, body)) if !Self::should_fail_over(status) => {
+                    let latency_ms = now_ms().saturating_sub(started_ms) as u32;
+                    ENDPOINT_HEALTH
+                        .write()
+                        .record_success(&endpoint, latency_ms);
+                    *CLUSTER_NET_STATE.write() = ClusterNetState::Success;
+
+                    return Ok(body);
+                }
+                Ok((_, body)) => {
+                    ENDPOINT_HEALTH.write().record_failure(&endpoint, now_ms());
+                    last_response.replace(body);
+                }
+                // Cancelling says nothing about the endpoint or the cluster
+                Err(RpcError::Cancelled) => return Err(RpcError::Cancelled),
+                Err(error) => {
+                    ENDPOINT_HEALTH.write().record_failure(&endpoint, now_ms());
+                    last_error = error;
+                }
+            }
+        }
+
+        // Every endpoint failed. An error status still carries a
+        // body the RPC layer can turn into a meaningful error.
+        if let Some(body) = last_response {
+            *CLUSTER_NET_STATE.write() = ClusterNetState::Success;
+
+            return Ok(body);
+        }
+
+        *CLUSTER_NET_STATE.write() = match last_error {
+            RpcError::Timeout(_) => ClusterNetState::Slow,
+            _ => ClusterNetState::Failure,
+        };
+
+        Err(last_error)
+    }
+
+    /// Sends the request to `url` instead of the active cluster, eg. to load
+    /// off-chain token metadata. Statuses other than 2xx are errors.
+    pub async fn send_to(self, url: &str) -> RpcResult<String> {
+        self.options.set_headers(&self.headers);
+
+        let (status, body) = self.fetch(url).await?;
+
+        if (200..300).contains(&status) {
+            Ok(body)
+        } else {
+            Err(RpcError::Transport(format!(
+                "{url} answered with HTTP {status}"
+            )))
+        }
+    }
+
+    /// Returns the status and body of the response. The timeout keeps
+    /// running while the body is read since a node can stall on either.
+    async fn fetch(&self, url: &str) -> RpcResult<(u16, String)> {
+        let controller = AbortController::new().map_err(WalletError::from)?;
+        self.options.set_signal(Some(&controller.signal()));
+
+        let request =
+            Request::new_with_str_and_init(url, &self.options).map_err(WalletError::from)?;
+
+        let timed_out = Rc::new(Cell::new(false));
+        let timeout = {
+            let controller = controller.clone();
+            let timed_out = timed_out.clone();
+
+            Timeout::new(self.timeout_ms, move || {
+                timed_out.set(true);
+                controller.abort();
+            })
+        };
+
+        if let Some(cancel_handle) = self.cancel_handle.as_ref() {
+            cancel_handle.register(&controller);
+        }
+
+        let response = Self::read_response(&request).await;
+
+        timeout.cancel();
+        if let Some(cancel_handle) = self.cancel_handle.as_ref() {
+            cancel_handle.unregister(&controller);
+        }
+
+        match response {
+            Ok(response) => Ok(response),
+            Err(_) if timed_out.get() => Err(RpcError::Timeout(self.timeout_ms)),
+            Err(_) if controller.signal().aborted() => Err(RpcError::Cancelled),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn read_response(request: &Request) -> WalletResult<(u16, String)> {
+        let fetch_promise = WALLET_ADAPTER.read().window().fetch_with_request(request);
+
+        // Await the fetch promise to get a `Response` object
+        let resp = JsFuture::from(fetch_promise)
+            .await?
+            .dyn_into::<Response>()?;
+
+        let body = JsFuture::from(resp.text()?)
             .await?
             .as_string()
             .ok_or(WalletError::Op(
                 "The response body is not a JsString".to_string(),
-            ))
+            ))?;
+
+        Ok((resp.status(), body))
     }
 
-    pub async fn build(&self) -> WalletResult<Response> {
-        self.options.set_headers(&self.headers);
+    /// Rate limits and server errors are worth retrying on another endpoint
+    fn should_fail_over(status: u16) -> bool {
+        status == 429 || (500..600).contains(&status)
+    }
+}
+
+/// Milliseconds since the unix epoch
+pub(crate) fn now_ms() -> u64 {
+    Date::now() as u64
+}
 
-        let url = CLUSTER_STORAGE
-            .read()
-            .active_cluster()
-            .endpoint()
-            .to_owned();
+/// [RpcTransport] that sends requests to the active cluster with [FetchReq]
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct BrowserTransport {
+    cancel_handle: Option<CancelHandle>,
+}
 
-        let request = Request::new_with_str_and_init(&url, &self.options)?;
+impl BrowserTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        let fetch_promise = WALLET_ADAPTER.read().window().fetch_with_request(&request);
+    /// Every request sent through the transport can be aborted with `cancel_handle`
+    pub fn add_cancel_handle(mut self, cancel_handle: CancelHandle) -> Self {
+        self.cancel_handle.replace(cancel_handle);
 
-        // Await the fetch promise to get a `Response` object
-        let resp_value = JsFuture::from(fetch_promise)
-            .await
-            .inspect(|_| {
-                *CLUSTER_NET_STATE.write() = ClusterNetState::Success;
-            })
-            .inspect_err(|_| {
-                *CLUSTER_NET_STATE.write() = ClusterNetState::Failure;
-            })?;
-        Ok(resp_value.dyn_into::<Response>()?)
+        self
+    }
+}
+
+impl RpcTransport for BrowserTransport {
+    async fn send(&self, body: &str) -> RpcResult<String> {
+        let mut request = FetchReq::new_for_rpc()?.set_body(body);
+
+        if let Some(cancel_handle) = self.cancel_handle.clone() {
+            request = request.add_cancel_handle(cancel_handle);
+        }
+
+        request.send().await
     }
 }
 
@@ -1,10 +1,12 @@
 use dioxus::prelude::*;
 
 use crate::{
-    trunk_cluster_name, utils::copied_address, views::ClusterNetState, ChangeWalletSvg, CloseSvg,
-    ClustersSvg, CopySvg, DisconnectSvg, FetchReq, GradientWalletIcon, Loader, NotificationInfo,
-    Route, WalletSvg, ACTIVE_CONNECTION, CLUSTER_NET_STATE, CLUSTER_STORAGE, GLOBAL_MESSAGE, LOGO,
-    WALLET_ADAPTER,MenuSvg,
+    poll_pending_transactions, signature_link, trunk_cluster_name, utils::copied_address,
+    views::ClusterNetState, ChangeWalletSvg, CheckSvg, CloseSvg, ClustersSvg, CopySvg,
+    DisconnectSvg, ErrorSvg, FetchReq, GradientWalletIcon, Loader, NotificationInfo,
+    PendingTransaction, Route, SignatureSvg, TransactionProgress, WalletSvg,
+    ACTIVE_CONNECTION, CLUSTER_NET_STATE, CLUSTER_STORAGE, GLOBAL_MESSAGE, LOGO,
+    PENDING_TRANSACTIONS, WALLET_ADAPTER,MenuSvg,
 };
 
 #[component]
@@ -36,6 +38,7 @@ pub fn Header() -> Element {
                     {NavItem(Route::Extras, "Extras")}
                     {NavClusterItem()}
                 }
+                PendingTransactionsTray {}
                 NavWalletItem{show_modal, show_connecting, shortened_address:shortened_address.clone(), show_mobile_close_button}
             }
 
@@ -66,6 +69,7 @@ pub fn Header() -> Element {
                                     {NavItem(Route::Extras, "Extras")}
                                     {NavClusterItem()}
                                 }
+                                PendingTransactionsTray {}
                                 NavWalletItem{show_modal, show_connecting, shortened_address, show_mobile_close_button}
                             }
                         }
@@ -349,26 +353,140 @@ fn PingCluster() -> Element {
         });
     });
 
-    if *CLUSTER_NET_STATE.read() == ClusterNetState::Failure {
-        rsx! {
-            div {class:"flex w-full justify-center min-h-[40px] bg-red-800 text-center items-center text-2xl justify-center items-center",
-                div{ class:"flex px-4 py-2 justify-center items-center",
-                    div{class:"flex flex-col md:flex-row w-full mr-2",
-                        span { class:"flex hidden md:inline-flex w-[30px] mr-1 text-white text-[30px] md:text-md", {ClustersSvg()}}
-                        {CLUSTER_STORAGE.read().active_cluster().name()} " cluster is unreachable!"
+    let (background, status) = match *CLUSTER_NET_STATE.read() {
+        ClusterNetState::Failure => ("bg-red-800", " cluster is unreachable!"),
+        ClusterNetState::Slow => ("bg-yellow-700", " cluster is slow to respond!"),
+        ClusterNetState::Success | ClusterNetState::Waiting => return rsx! {},
+    };
+
+    rsx! {
+        div {class:"flex w-full justify-center min-h-[40px] {background} text-center items-center text-2xl justify-center items-center",
+            div{ class:"flex px-4 py-2 justify-center items-center",
+                div{class:"flex flex-col md:flex-row w-full mr-2",
+                    span { class:"flex hidden md:inline-flex w-[30px] mr-1 text-white text-[30px] md:text-md", {ClustersSvg()}}
+                    {CLUSTER_STORAGE.read().active_cluster().name()} {status}
+                }
+                button {
+                    onclick:move|_| {
+                        let active_cluster = CLUSTER_STORAGE.read().active_cluster().clone();
+                        CLUSTER_STORAGE.write().set_active_cluster(active_cluster);
+                    },
+                    class:"flex bg-true-blue items-center justify-center text-sm text-white px-2 py-1 rounded-full hover:bg-cobalt-blue",
+                    "REFRESH"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn PendingTransactionsTray() -> Element {
+    let mut show_tray = use_signal(|| false);
+
+    // Statuses are only polled on the cluster a transaction was sent to
+    use_effect(move || {
+        CLUSTER_STORAGE.read();
+        poll_pending_transactions();
+    });
+
+    if PENDING_TRANSACTIONS.read().is_empty() {
+        return rsx! {};
+    }
+
+    let unsettled_count = PENDING_TRANSACTIONS.read().unsettled_count();
+
+    rsx! {
+        div { class:"relative flex items-center justify-center mt-5 md:mt-0",
+            div {
+                onclick:move|_| {
+                    let shown = *show_tray.read();
+                    show_tray.set(!shown);
+                },
+                title:"Transactions",
+                class:"flex items-center cursor-pointer text-white bg-true-blue hover:bg-true-yonder rounded-full py-1 px-3",
+                span{class:"flex w-[20px] mr-1", {SignatureSvg()}}
+                if unsettled_count > 0 {
+                    span{class:"flex mr-1", {Loader()}}
+                    span{class:"text-sm", "{unsettled_count}"}
+                }else {
+                    span{class:"flex w-[15px]", {CheckSvg()}}
+                }
+            }
+
+            if *show_tray.read() {
+                div {class:"absolute top-10 right-0 z-50 flex flex-col w-[300px] p-2 text-white bg-true-blue rounded-lg shadow-xl",
+                    div {class:"flex w-full justify-between items-center mb-2 text-sm",
+                        span{"Transactions"}
+                        button {
+                            onclick:move|_| {
+                                PENDING_TRANSACTIONS.write().clear_settled();
+                            },
+                            class:"text-xs underline",
+                            "CLEAR SETTLED"
+                        }
+                    }
+                    for transaction in PENDING_TRANSACTIONS.read().transactions() {
+                        PendingTransactionItem{ transaction: transaction.clone() }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn PendingTransactionItem(transaction: PendingTransaction) -> Element {
+    let shortened_signature = wallet_adapter::Utils::shorten_base58(transaction.signature())
+        .map(|signature| signature.to_string())
+        .unwrap_or(String::from("Invalid Signature"));
+
+    let progress = transaction.progress().clone();
+    let step = progress.step();
+    let signature = transaction.signature().to_string();
+
+    rsx! {
+        div {class:"flex flex-col w-full p-2 mb-2 rounded-lg bg-cobalt-blue text-sm",
+            div {class:"flex w-full justify-between items-center",
+                span{ {transaction.description()} }
+                span{class:"text-xs", {trunk_cluster_name(transaction.cluster_name())}}
+            }
+            div {class:"flex w-full items-center mt-1",
+                span{class:"flex w-[15px] mr-1", {SignatureSvg()}}
+                {signature_link(transaction.signature(), &shortened_signature)}
+            }
+            div {class:"flex w-full gap-1 mt-2",
+                for index in 1..=3usize {
+                    div {
+                        class:"h-1 w-1/3 rounded-full",
+                        class:if progress.error().is_some() {"bg-red-600"} else if progress == TransactionProgress::Expired {"bg-yellow-500"} else if index <= step {"bg-green-500"} else {"bg-gray-400"},
                     }
+                }
+            }
+            div {class:"flex w-full justify-between items-center mt-1 text-xs",
+                div {class:"flex items-center",
+                    if progress.error().is_some() || progress == TransactionProgress::Expired {
+                        span{class:"flex w-[15px] mr-1", {ErrorSvg()}}
+                    }else if progress.is_settled() {
+                        span{class:"flex w-[15px] mr-1", {CheckSvg()}}
+                    }
+                    "{progress}"
+                }
+                if progress.is_settled() {
                     button {
                         onclick:move|_| {
-                            let active_cluster = CLUSTER_STORAGE.read().active_cluster().clone();
-                            CLUSTER_STORAGE.write().set_active_cluster(active_cluster);
+                            PENDING_TRANSACTIONS.write().remove(&signature);
                         },
-                        class:"flex bg-true-blue items-center justify-center text-sm text-white px-2 py-1 rounded-full hover:bg-cobalt-blue",
-                        "REFRESH"
+                        class:"underline",
+                        "DISMISS"
                     }
                 }
             }
+            if let Some(error) = progress.error() {
+                div {class:"flex w-full mt-1 text-xs text-red-200 break-all", "{error}"}
+            }
+            if progress == TransactionProgress::Expired {
+                div {class:"flex w-full mt-1 text-xs text-yellow-200", "The blockhash expired before the transaction landed, send it again"}
+            }
         }
-    } else {
-        rsx! {}
     }
 }
@@ -0,0 +1,193 @@
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    message::{
+        v0::{self, LoadedAddresses},
+        AddressLookupTableAccount, VersionedMessage,
+    },
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use crate::{
+    AccountInfoConfig, EncodedAccount, Encoding, RpcClient, RpcError, RpcResult, RpcTransport,
+};
+
+pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: &str = "AddressLookupTab1e1111111111111111111111111";
+
+/// The addresses of a lookup table follow its metadata
+const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+/// The discriminant of an initialized table in the lookup table program state
+const LOOKUP_TABLE_DISCRIMINANT: u32 = 1;
+
+/// Compiles `instructions` to a v0 message, accounts found in `lookup_tables`
+/// are referenced by their index in the table instead of their address.
+/// Each signature is left empty for the wallet to fill in.
+pub fn versioned_transaction(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> RpcResult<VersionedTransaction> {
+    let message = v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)
+        .map_err(|error| RpcError::Build(error.to_string()))?;
+
+    Ok(VersionedTransaction {
+        signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+        message: VersionedMessage::V0(message),
+    })
+}
+
+/// Decodes the data of an address lookup table account
+pub fn parse_lookup_table(key: Pubkey, data: &[u8]) -> RpcResult<AddressLookupTableAccount> {
+    let invalid = || RpcError::Deserialize(format!("{key} is not an address lookup table"));
+
+    let discriminant = data
+        .get(..4)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or_else(invalid)?;
+    let addresses = data.get(LOOKUP_TABLE_META_SIZE..).ok_or_else(invalid)?;
+
+    if discriminant != LOOKUP_TABLE_DISCRIMINANT || addresses.len() % 32 != 0 {
+        return Err(invalid());
+    }
+
+    Ok(AddressLookupTableAccount {
+        key,
+        addresses: addresses
+            .chunks_exact(32)
+            .map(|address| Pubkey::new_from_array(address.try_into().unwrap_or_default()))
+            .collect(),
+    })
+}
+
+/// Loads the lookup table at `address` with `getAccountInfo`
+pub async fn fetch_lookup_table<T: RpcTransport>(
+    client: &RpcClient<T>,
+    address: &Pubkey,
+) -> RpcResult<AddressLookupTableAccount> {
+    let account = client
+        .get_account_info::<EncodedAccount>(
+            &address.to_string(),
+            AccountInfoConfig::new(Encoding::Base64),
+        )
+        .await?
+        .ok_or(RpcError::Deserialize(format!(
+            "The address lookup table {address} does not exist"
+        )))?;
+
+    if account.owner != ADDRESS_LOOKUP_TABLE_PROGRAM_ID {
+        return Err(RpcError::Deserialize(format!(
+            "{address} is not an address lookup table"
+        )));
+    }
+
+    parse_lookup_table(*address, &account.decoded_data().unwrap_or_default())
+}
+
+/// The accounts a v0 `message` loads from lookup tables, legacy messages load none
+pub async fn loaded_addresses<T: RpcTransport>(
+    client: &RpcClient<T>,
+    message: &VersionedMessage,
+) -> RpcResult<LoadedAddresses> {
+    let mut loaded = LoadedAddresses::default();
+
+    for lookup in message.address_table_lookups().unwrap_or_default() {
+        let table = fetch_lookup_table(client, &lookup.account_key).await?;
+
+        let resolve = |indexes: &[u8]| {
+            indexes
+                .iter()
+                .map(|index| {
+                    table
+                        .addresses
+                        .get(*index as usize)
+                        .copied()
+                        .ok_or(RpcError::Deserialize(format!(
+                            "Index {index} is outside the address lookup table {}",
+                            table.key
+                        )))
+                })
+                .collect::<RpcResult<Vec<Pubkey>>>()
+        };
+
+        loaded.writable.extend(resolve(&lookup.writable_indexes)?);
+        loaded.readonly.extend(resolve(&lookup.readonly_indexes)?);
+    }
+
+    Ok(loaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use solana_sdk::system_instruction::transfer;
+
+    use super::*;
+    use crate::{block_on, encoded_account, MockTransport, RpcFixture};
+
+    fn lookup_table_data(addresses: &[Pubkey]) -> Vec<u8> {
+        let mut data = vec![0u8; LOOKUP_TABLE_META_SIZE];
+        data[..4].copy_from_slice(&LOOKUP_TABLE_DISCRIMINANT.to_le_bytes());
+        // Not deactivated
+        data[4..12].copy_from_slice(&u64::MAX.to_le_bytes());
+        addresses
+            .iter()
+            .for_each(|address| data.extend_from_slice(address.as_ref()));
+
+        data
+    }
+
+    #[test]
+    fn compiles_against_lookup_tables() {
+        let payer = Pubkey::new_unique();
+        let recipients = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let table_key = Pubkey::new_unique();
+
+        let client = RpcClient::with_transport(MockTransport::replay(vec![RpcFixture {
+            method: "getAccountInfo".to_string(),
+            params: json!([table_key.to_string(), { "encoding": "base64" }]),
+            response: json!({
+                "jsonrpc": "2.0",
+                "result": {
+                    "context": { "slot": 1 },
+                    "value": encoded_account(
+                        ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+                        1_600_000,
+                        &lookup_table_data(&recipients)
+                    )
+                }
+            }),
+        }]));
+
+        let table = block_on(fetch_lookup_table(&client, &table_key)).unwrap();
+        assert_eq!(table.addresses, recipients);
+
+        let instructions = recipients
+            .iter()
+            .map(|recipient| transfer(&payer, recipient, 1_000))
+            .collect::<Vec<Instruction>>();
+        let tx = versioned_transaction(&payer, &instructions, &[table], Hash::default()).unwrap();
+
+        assert_eq!(tx.signatures, [Signature::default()]);
+        // The recipients are only referenced through the table
+        assert!(!tx.message.static_account_keys().contains(&recipients[0]));
+        assert_eq!(tx.message.address_table_lookups().unwrap().len(), 1);
+
+        // The wallet returns the same bytes with the signature filled in
+        let bytes = bincode::serialize(&tx).unwrap();
+        assert_eq!(
+            bincode::deserialize::<VersionedTransaction>(&bytes).unwrap(),
+            tx
+        );
+
+        let loaded = block_on(loaded_addresses(&client, &tx.message)).unwrap();
+        assert_eq!(loaded.writable, recipients);
+        assert!(loaded.readonly.is_empty());
+
+        assert!(parse_lookup_table(table_key, &[1, 0, 0, 0]).is_err());
+    }
+}
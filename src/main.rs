@@ -20,6 +20,45 @@ pub(crate) use svg_assets::*;
 mod dioxus_adapter;
 pub(crate) use dioxus_adapter::*;
 
+mod rpc;
+pub(crate) use rpc::*;
+
+mod pending_transactions;
+pub(crate) use pending_transactions::*;
+
+mod amount;
+pub(crate) use amount::*;
+
+mod token_metadata;
+pub(crate) use token_metadata::*;
+
+mod transaction_detail;
+pub(crate) use transaction_detail::*;
+
+mod account_info;
+pub(crate) use account_info::*;
+
+mod token_transfer;
+pub(crate) use token_transfer::*;
+
+mod close_accounts;
+pub(crate) use close_accounts::*;
+
+mod transaction_review;
+pub(crate) use transaction_review::*;
+
+mod priority_fees;
+pub(crate) use priority_fees::*;
+
+mod lookup_tables;
+pub(crate) use lookup_tables::*;
+
+mod nonce_accounts;
+pub(crate) use nonce_accounts::*;
+
+mod transaction_composer;
+pub(crate) use transaction_composer::*;
+
 mod fetch_util;
 pub(crate) use fetch_util::*;
 
@@ -0,0 +1,447 @@
+use dioxus::prelude::*;
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    system_instruction::{
+        advance_nonce_account, create_nonce_account_with_seed, withdraw_nonce_account,
+    },
+};
+use wallet_adapter::{WalletError, WalletResult};
+
+use crate::{
+    budgeted_transaction, program_id, AccountInfoConfig, CommitmentConfig, EncodedAccount,
+    Encoding, Lamports, PreparedTransaction, RpcClient, RpcError, RpcResult, RpcTransport,
+    DURABLE_NONCE, SYSTEM_PROGRAM_ID,
+};
+
+/// Size of a nonce account, its data is the bincode encoded `nonce::state::Versions`
+pub const NONCE_ACCOUNT_LENGTH: usize = 80;
+
+/// Nonce accounts of a wallet are derived from it with the seeds `nonce-0` and up
+pub const MAX_NONCE_ACCOUNTS: usize = 4;
+
+/// `State::Initialized` in the nonce account state
+const NONCE_STATE_INITIALIZED: u32 = 1;
+
+/// `AdvanceNonceAccount` in the system instruction enum, bincode encoded
+const ADVANCE_NONCE_ACCOUNT: [u8; 4] = [4, 0, 0, 0];
+
+/// An initialized nonce account, its stored nonce replaces the recent
+/// blockhash of a transaction until the account is advanced
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct NonceAccount {
+    pub address: Pubkey,
+    /// The only key allowed to advance the nonce or withdraw from the account
+    pub authority: Pubkey,
+    pub nonce: Hash,
+    pub lamports_per_signature: u64,
+    pub lamports: Lamports,
+}
+
+impl NonceAccount {
+    /// Decodes the data of a system account holding a nonce
+    pub fn parse(address: Pubkey, lamports: Lamports, data: &[u8]) -> RpcResult<Self> {
+        let invalid = || RpcError::Deserialize(format!("{address} is not a nonce account"));
+
+        if data.len() != NONCE_ACCOUNT_LENGTH {
+            return Err(invalid());
+        }
+
+        let state = u32::from_le_bytes(data[4..8].try_into().map_err(|_| invalid())?);
+        if state != NONCE_STATE_INITIALIZED {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            address,
+            authority: Pubkey::try_from(&data[8..40]).map_err(|_| invalid())?,
+            nonce: Hash::new_from_array(data[40..72].try_into().map_err(|_| invalid())?),
+            lamports_per_signature: u64::from_le_bytes(
+                data[72..80].try_into().map_err(|_| invalid())?,
+            ),
+            lamports,
+        })
+    }
+
+    /// Has to be the first instruction of a transaction using the nonce
+    pub fn advance_instruction(&self) -> Instruction {
+        advance_nonce_account(&self.address, &self.authority)
+    }
+}
+
+/// Whether `instruction` advances a nonce, which makes its transaction durable
+pub fn is_advance_nonce(instruction: &Instruction) -> bool {
+    instruction.program_id == program_id(SYSTEM_PROGRAM_ID)
+        && instruction.data == ADVANCE_NONCE_ACCOUNT
+}
+
+/// The nonce account and the nonce `message` was built with, `None`
+/// unless its first instruction advances a nonce
+pub fn durable_nonce(message: &VersionedMessage) -> Option<(Pubkey, Hash)> {
+    let keys = message.static_account_keys();
+    let first = message.instructions().first()?;
+
+    if keys.get(first.program_id_index as usize)? != &program_id(SYSTEM_PROGRAM_ID)
+        || first.data != ADVANCE_NONCE_ACCOUNT
+    {
+        return None;
+    }
+
+    let nonce_account = keys.get(*first.accounts.first()? as usize)?;
+
+    Some((*nonce_account, *message.recent_blockhash()))
+}
+
+/// Loads the nonce account at `address`
+pub async fn fetch_nonce_account<T: RpcTransport>(
+    client: &RpcClient<T>,
+    address: &Pubkey,
+) -> RpcResult<NonceAccount> {
+    client
+        .get_account_info::<EncodedAccount>(
+            &address.to_string(),
+            AccountInfoConfig::new(Encoding::Base64),
+        )
+        .await?
+        .ok_or(RpcError::Deserialize(format!(
+            "The nonce account {address} does not exist"
+        )))
+        .and_then(|account| parse_encoded(*address, &account))
+}
+
+fn parse_encoded(address: Pubkey, account: &EncodedAccount) -> RpcResult<NonceAccount> {
+    if account.owner != SYSTEM_PROGRAM_ID {
+        return Err(RpcError::Deserialize(format!(
+            "{address} is not a nonce account"
+        )));
+    }
+
+    NonceAccount::parse(
+        address,
+        Lamports::new(account.lamports),
+        &account.decoded_data().unwrap_or_default(),
+    )
+}
+
+/// A nonce account address derived from the wallet, `account` is `None`
+/// until it is created
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NonceSlot {
+    pub seed: String,
+    pub address: Pubkey,
+    pub account: Option<NonceAccount>,
+}
+
+/// The address of the nonce account derived from `owner` with `seed`
+pub fn nonce_account_address(owner: &Pubkey, seed: &str) -> Pubkey {
+    Pubkey::create_with_seed(owner, seed, &program_id(SYSTEM_PROGRAM_ID)).unwrap_or_default()
+}
+
+/// Loads the [MAX_NONCE_ACCOUNTS] nonce accounts derived from `owner` in one call
+pub async fn load_nonce_accounts<T: RpcTransport>(
+    client: &RpcClient<T>,
+    owner: &Pubkey,
+) -> RpcResult<Vec<NonceSlot>> {
+    let addresses = (0..MAX_NONCE_ACCOUNTS)
+        .map(|index| {
+            let seed = format!("nonce-{index}");
+            let address = nonce_account_address(owner, &seed);
+
+            (seed, address)
+        })
+        .collect::<Vec<(String, Pubkey)>>();
+
+    let accounts = client
+        .get_multiple_accounts::<EncodedAccount>(
+            &addresses
+                .iter()
+                .map(|(_, address)| address.to_string())
+                .collect::<Vec<String>>(),
+            AccountInfoConfig::new(Encoding::Base64),
+        )
+        .await?;
+
+    Ok(addresses
+        .into_iter()
+        .zip(accounts)
+        .map(|((seed, address), account)| NonceSlot {
+            account: account.and_then(|account| parse_encoded(address, &account).ok()),
+            seed,
+            address,
+        })
+        .collect())
+}
+
+/// Where a transaction gets its recent blockhash from
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TransactionLifetime {
+    /// Expires once the block height passes `last_valid_block_height`
+    Blockhash {
+        blockhash: Hash,
+        last_valid_block_height: u64,
+    },
+    /// Valid until the nonce account is advanced
+    DurableNonce(NonceAccount),
+}
+
+impl TransactionLifetime {
+    /// Uses the latest blockhash of the cluster
+    pub async fn latest<T: RpcTransport>(client: &RpcClient<T>) -> WalletResult<Self> {
+        let blockhash = client
+            .get_latest_blockhash(CommitmentConfig::default())
+            .await?;
+
+        Ok(Self::Blockhash {
+            blockhash: blockhash.hash()?,
+            last_valid_block_height: blockhash.last_valid_block_height,
+        })
+    }
+
+    /// Uses the nonce account selected in [DURABLE_NONCE], the latest
+    /// blockhash otherwise. `payer` has to be the authority of the nonce.
+    pub async fn resolve<T: RpcTransport>(
+        client: &RpcClient<T>,
+        payer: &Pubkey,
+    ) -> WalletResult<Self> {
+        let Some(address) = *DURABLE_NONCE.read() else {
+            return Self::latest(client).await;
+        };
+
+        let nonce_account = fetch_nonce_account(client, &address).await?;
+        if nonce_account.authority != *payer {
+            return Err(WalletError::Op(format!(
+                "The connected wallet is not the authority of the nonce account {address}"
+            )));
+        }
+
+        Ok(Self::DurableNonce(nonce_account))
+    }
+
+    pub fn recent_blockhash(&self) -> Hash {
+        match self {
+            Self::Blockhash { blockhash, .. } => *blockhash,
+            Self::DurableNonce(nonce_account) => nonce_account.nonce,
+        }
+    }
+
+    /// A durable transaction does not expire with the block height, it
+    /// expires once its nonce is advanced, see [durable_nonce]
+    pub fn last_valid_block_height(&self) -> u64 {
+        match self {
+            Self::Blockhash {
+                last_valid_block_height,
+                ..
+            } => *last_valid_block_height,
+            Self::DurableNonce(_) => u64::MAX,
+        }
+    }
+
+    /// `instructions` preceded by the advance nonce instruction of a durable transaction
+    pub fn instructions(&self, instructions: &[Instruction]) -> Vec<Instruction> {
+        match self {
+            Self::Blockhash { .. } => instructions.to_vec(),
+            Self::DurableNonce(nonce_account) => {
+                let mut durable = vec![nonce_account.advance_instruction()];
+                durable.extend_from_slice(instructions);
+
+                durable
+            }
+        }
+    }
+
+    pub fn is_durable(&self) -> bool {
+        matches!(self, Self::DurableNonce(_))
+    }
+}
+
+/// Creates the nonce account of `slot` with the connected wallet as its authority
+pub async fn prepare_create_nonce_account(
+    slot: &NonceSlot,
+    public_key_bytes: [u8; 32],
+) -> WalletResult<PreparedTransaction> {
+    let client = RpcClient::new();
+    let owner = Pubkey::new_from_array(public_key_bytes);
+
+    let rent = client
+        .get_minimum_balance_for_rent_exemption(NONCE_ACCOUNT_LENGTH, CommitmentConfig::default())
+        .await?;
+    let instructions =
+        create_nonce_account_with_seed(&owner, &slot.address, &owner, &slot.seed, &owner, rent);
+
+    prepare_nonce_transaction(
+        &client,
+        &instructions,
+        &owner,
+        &format!("Create nonce account for {} SOL", Lamports::new(rent)),
+    )
+    .await
+}
+
+/// Stores a new nonce, every transaction signed with the current one becomes invalid
+pub async fn prepare_advance_nonce(
+    nonce_account: &NonceAccount,
+    public_key_bytes: [u8; 32],
+) -> WalletResult<PreparedTransaction> {
+    let client = RpcClient::new();
+    let owner = Pubkey::new_from_array(public_key_bytes);
+
+    prepare_nonce_transaction(
+        &client,
+        &[nonce_account.advance_instruction()],
+        &owner,
+        "Advance nonce",
+    )
+    .await
+}
+
+/// Withdraws every lamport, which closes the nonce account
+pub async fn prepare_withdraw_nonce(
+    nonce_account: &NonceAccount,
+    public_key_bytes: [u8; 32],
+) -> WalletResult<PreparedTransaction> {
+    let client = RpcClient::new();
+    let owner = Pubkey::new_from_array(public_key_bytes);
+
+    let instruction = withdraw_nonce_account(
+        &nonce_account.address,
+        &nonce_account.authority,
+        &owner,
+        nonce_account.lamports.get(),
+    );
+
+    prepare_nonce_transaction(
+        &client,
+        &[instruction],
+        &owner,
+        &format!("Withdraw {} SOL from nonce account", nonce_account.lamports),
+    )
+    .await
+}
+
+/// Transactions managing nonce accounts always use the latest blockhash
+async fn prepare_nonce_transaction<T: RpcTransport>(
+    client: &RpcClient<T>,
+    instructions: &[Instruction],
+    owner: &Pubkey,
+    description: &str,
+) -> WalletResult<PreparedTransaction> {
+    let lifetime = TransactionLifetime::latest(client).await?;
+    let tx = budgeted_transaction(client, instructions, owner, &[], &lifetime).await?;
+
+    Ok(PreparedTransaction::send(
+        tx,
+        lifetime.last_valid_block_height(),
+        description,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{block_on, encoded_account, versioned_transaction, MockTransport, RpcFixture};
+
+    fn nonce_data(authority: &Pubkey, nonce: &Hash) -> Vec<u8> {
+        let mut data = Vec::<u8>::new();
+        // `Versions::Current` and `State::Initialized`
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&NONCE_STATE_INITIALIZED.to_le_bytes());
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(nonce.as_ref());
+        data.extend_from_slice(&5000u64.to_le_bytes());
+
+        data
+    }
+
+    #[test]
+    fn loads_derived_nonce_accounts() {
+        let owner = Pubkey::new_unique();
+        let nonce = Hash::new_unique();
+        let addresses = (0..MAX_NONCE_ACCOUNTS)
+            .map(|index| nonce_account_address(&owner, &format!("nonce-{index}")))
+            .collect::<Vec<Pubkey>>();
+
+        let client = RpcClient::with_transport(MockTransport::replay(vec![RpcFixture {
+            method: "getMultipleAccounts".to_string(),
+            params: json!([
+                addresses.iter().map(Pubkey::to_string).collect::<Vec<String>>(),
+                { "encoding": "base64" }
+            ]),
+            response: json!({
+                "jsonrpc": "2.0",
+                "result": {
+                    "context": { "slot": 1 },
+                    "value": [
+                        encoded_account(SYSTEM_PROGRAM_ID, 1_447_680, &nonce_data(&owner, &nonce)),
+                        null,
+                        // Accounts that do not hold a nonce are skipped
+                        encoded_account(SYSTEM_PROGRAM_ID, 1_447_680, &[]),
+                        null
+                    ]
+                }
+            }),
+        }]));
+
+        let slots = block_on(load_nonce_accounts(&client, &owner)).unwrap();
+
+        assert_eq!(slots.len(), MAX_NONCE_ACCOUNTS);
+        assert_eq!(slots[0].seed, "nonce-0");
+        assert_eq!(
+            slots[0].account,
+            Some(NonceAccount {
+                address: addresses[0],
+                authority: owner,
+                nonce,
+                lamports_per_signature: 5000,
+                lamports: Lamports::new(1_447_680),
+            })
+        );
+        assert!(slots[1..].iter().all(|slot| slot.account.is_none()));
+    }
+
+    #[test]
+    fn durable_transactions_advance_the_nonce_first() {
+        let authority = Pubkey::new_unique();
+        let nonce_account = NonceAccount {
+            address: Pubkey::new_unique(),
+            authority,
+            nonce: Hash::new_unique(),
+            lamports_per_signature: 5000,
+            lamports: Lamports::new(1_447_680),
+        };
+        let transfer = solana_sdk::system_instruction::transfer(&authority, &authority, 1);
+
+        let lifetime = TransactionLifetime::DurableNonce(nonce_account);
+        let instructions = lifetime.instructions(std::slice::from_ref(&transfer));
+
+        assert!(lifetime.is_durable());
+        assert_eq!(lifetime.recent_blockhash(), nonce_account.nonce);
+        assert_eq!(lifetime.last_valid_block_height(), u64::MAX);
+        assert_eq!(instructions.len(), 2);
+        assert!(is_advance_nonce(&instructions[0]));
+        assert!(!is_advance_nonce(&transfer));
+
+        // The rebroadcast of the signed transaction watches the nonce it was built with
+        let tx = versioned_transaction(&authority, &instructions, &[], lifetime.recent_blockhash())
+            .unwrap();
+        assert_eq!(
+            durable_nonce(&tx.message),
+            Some((nonce_account.address, nonce_account.nonce))
+        );
+
+        let lifetime = TransactionLifetime::Blockhash {
+            blockhash: Hash::new_unique(),
+            last_valid_block_height: 100,
+        };
+        assert_eq!(
+            lifetime.instructions(std::slice::from_ref(&transfer)),
+            std::slice::from_ref(&transfer)
+        );
+
+        let tx = versioned_transaction(&authority, &[transfer], &[], Hash::default()).unwrap();
+        assert_eq!(durable_nonce(&tx.message), None);
+    }
+}
@@ -0,0 +1,498 @@
+use dioxus::prelude::*;
+use gloo_timers::future::TimeoutFuture;
+use solana_sdk::{hash::Hash, pubkey::Pubkey};
+use solana_transaction_error::TransactionError;
+
+use crate::{
+    fetch_nonce_account, Commitment, CommitmentConfig, RpcClient, RpcTransport,
+    SendTransactionConfig, SignatureStatus, CLUSTER_STORAGE, PENDING_TRANSACTIONS,
+};
+
+const POLL_INTERVAL_MS: u32 = 2000;
+
+/// `getSignatureStatuses` accepts at most this many signatures per call
+const MAX_SIGNATURES_PER_CALL: usize = 256;
+
+/// The number of blocks a blockhash stays valid for
+const MAX_PROCESSING_AGE: u64 = 150;
+
+/// How far a submitted transaction has made it through the cluster
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TransactionProgress {
+    Submitted,
+    Processed,
+    Confirmed,
+    Finalized,
+    Failed(TransactionError),
+    /// The blockhash expired before the cluster saw the transaction,
+    /// it can never land and has to be signed again
+    Expired,
+}
+
+impl TransactionProgress {
+    /// The status no longer changes once the transaction is finalized, failed or expired
+    pub fn is_settled(&self) -> bool {
+        matches!(self, Self::Finalized | Self::Failed(_) | Self::Expired)
+    }
+
+    /// The number of commitment levels reached, from 0 to 3
+    pub fn step(&self) -> usize {
+        match self {
+            Self::Submitted => 0,
+            Self::Processed => 1,
+            Self::Confirmed => 2,
+            Self::Finalized | Self::Failed(_) | Self::Expired => 3,
+        }
+    }
+
+    pub fn error(&self) -> Option<&TransactionError> {
+        match self {
+            Self::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<&SignatureStatus> for TransactionProgress {
+    fn from(status: &SignatureStatus) -> Self {
+        if let Some(error) = status.err.as_ref() {
+            return Self::Failed(error.clone());
+        }
+
+        match status.confirmation_status {
+            Some(Commitment::Finalized) => Self::Finalized,
+            Some(Commitment::Confirmed) => Self::Confirmed,
+            // Older nodes leave out `confirmationStatus`, the
+            // transaction is rooted once `confirmations` is `null`
+            None if status.confirmations.is_none() => Self::Finalized,
+            Some(Commitment::Processed) | None => Self::Processed,
+        }
+    }
+}
+
+impl core::fmt::Display for TransactionProgress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Submitted => write!(f, "SUBMITTED"),
+            Self::Processed => write!(f, "PROCESSED"),
+            Self::Confirmed => write!(f, "CONFIRMED"),
+            Self::Finalized => write!(f, "FINALIZED"),
+            Self::Failed(_) => write!(f, "FAILED"),
+            Self::Expired => write!(f, "EXPIRED"),
+        }
+    }
+}
+
+/// A signed transaction that is sent again until the cluster sees
+/// it or its blockhash expires
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Rebroadcast {
+    wire_transaction: String,
+    last_valid_block_height: u64,
+    /// The nonce account and nonce of a durable transaction
+    durable_nonce: Option<(Pubkey, Hash)>,
+}
+
+impl Rebroadcast {
+    /// `wire_transaction` is the base64 encoded signed transaction
+    pub fn new(wire_transaction: &str, last_valid_block_height: u64) -> Self {
+        Self {
+            wire_transaction: wire_transaction.to_string(),
+            last_valid_block_height,
+            durable_nonce: None,
+        }
+    }
+
+    /// The transaction expires once the nonce stored in `nonce_account` is no longer `nonce`
+    pub fn add_durable_nonce(mut self, nonce_account: Pubkey, nonce: Hash) -> Self {
+        self.durable_nonce.replace((nonce_account, nonce));
+
+        self
+    }
+
+    pub fn durable_nonce(&self) -> Option<(Pubkey, Hash)> {
+        self.durable_nonce
+    }
+
+    pub fn wire_transaction(&self) -> &str {
+        self.wire_transaction.as_str()
+    }
+
+    pub fn last_valid_block_height(&self) -> u64 {
+        self.last_valid_block_height
+    }
+
+    pub fn is_expired(&self, block_height: u64) -> bool {
+        block_height > self.last_valid_block_height
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PendingTransaction {
+    signature: String,
+    description: String,
+    cluster_name: String,
+    progress: TransactionProgress,
+    rebroadcast: Option<Rebroadcast>,
+    /// Without a [Rebroadcast] the blockhash is unknown, it is valid for at
+    /// most [MAX_PROCESSING_AGE] blocks after the first poll
+    last_valid_block_height: Option<u64>,
+}
+
+impl PendingTransaction {
+    pub fn new(signature: &str, description: &str, cluster_name: &str) -> Self {
+        Self {
+            signature: signature.to_string(),
+            description: description.to_string(),
+            cluster_name: cluster_name.to_string(),
+            progress: TransactionProgress::Submitted,
+            rebroadcast: None,
+            last_valid_block_height: None,
+        }
+    }
+
+    pub fn add_rebroadcast(mut self, rebroadcast: Rebroadcast) -> Self {
+        self.rebroadcast = Some(rebroadcast);
+
+        self
+    }
+
+    pub fn signature(&self) -> &str {
+        self.signature.as_str()
+    }
+
+    pub fn description(&self) -> &str {
+        self.description.as_str()
+    }
+
+    /// The cluster the transaction was sent to
+    pub fn cluster_name(&self) -> &str {
+        self.cluster_name.as_str()
+    }
+
+    pub fn progress(&self) -> &TransactionProgress {
+        &self.progress
+    }
+
+    pub fn rebroadcast(&self) -> Option<&Rebroadcast> {
+        self.rebroadcast.as_ref()
+    }
+}
+
+/// Transactions sent from the app, newest first
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct PendingTransactions {
+    transactions: Vec<PendingTransaction>,
+    polling: bool,
+}
+
+impl PendingTransactions {
+    pub fn add(&mut self, transaction: PendingTransaction) -> &mut Self {
+        if !self
+            .transactions
+            .iter()
+            .any(|current| current.signature == transaction.signature)
+        {
+            self.transactions.insert(0, transaction);
+        }
+
+        self
+    }
+
+    pub fn transactions(&self) -> &[PendingTransaction] {
+        self.transactions.as_slice()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    pub fn unsettled_count(&self) -> usize {
+        self.transactions
+            .iter()
+            .filter(|transaction| !transaction.progress.is_settled())
+            .count()
+    }
+
+    /// Signatures sent to `cluster_name` that still need polling
+    pub fn unsettled_signatures(&self, cluster_name: &str) -> Vec<String> {
+        self.transactions
+            .iter()
+            .filter(|transaction| {
+                transaction.cluster_name == cluster_name && !transaction.progress.is_settled()
+            })
+            .map(|transaction| transaction.signature.clone())
+            .collect()
+    }
+
+    /// Transactions sent to `cluster_name` that the cluster has not seen yet
+    /// and can still be rebroadcast
+    pub fn unseen_rebroadcasts(&self, cluster_name: &str) -> Vec<(String, Rebroadcast)> {
+        self.transactions
+            .iter()
+            .filter(|transaction| {
+                transaction.cluster_name == cluster_name
+                    && transaction.progress == TransactionProgress::Submitted
+            })
+            .filter_map(|transaction| {
+                transaction
+                    .rebroadcast
+                    .clone()
+                    .map(|rebroadcast| (transaction.signature.clone(), rebroadcast))
+            })
+            .collect()
+    }
+
+    /// Whether a transaction sent to `cluster_name` without a [Rebroadcast]
+    /// has not been seen by the cluster yet
+    pub fn has_unseen_without_rebroadcast(&self, cluster_name: &str) -> bool {
+        self.transactions.iter().any(|transaction| {
+            transaction.cluster_name == cluster_name
+                && transaction.progress == TransactionProgress::Submitted
+                && transaction.rebroadcast.is_none()
+        })
+    }
+
+    /// Marks the transactions sent to `cluster_name` without a [Rebroadcast]
+    /// as expired once `block_height` is past [MAX_PROCESSING_AGE] blocks
+    /// after they were first polled, so they are not polled forever
+    pub fn expire_unseen(&mut self, cluster_name: &str, block_height: u64) -> &mut Self {
+        self.transactions
+            .iter_mut()
+            .filter(|transaction| {
+                transaction.cluster_name == cluster_name
+                    && transaction.progress == TransactionProgress::Submitted
+                    && transaction.rebroadcast.is_none()
+            })
+            .for_each(|transaction| {
+                let last_valid_block_height = *transaction
+                    .last_valid_block_height
+                    .get_or_insert(block_height + MAX_PROCESSING_AGE);
+
+                if block_height > last_valid_block_height {
+                    transaction.progress = TransactionProgress::Expired;
+                }
+            });
+
+        self
+    }
+
+    pub fn set_progress(&mut self, signature: &str, progress: TransactionProgress) -> &mut Self {
+        if let Some(transaction) = self
+            .transactions
+            .iter_mut()
+            .find(|transaction| transaction.signature == signature)
+        {
+            // Once seen by the cluster the signed bytes are no longer needed
+            if progress != TransactionProgress::Submitted {
+                transaction.rebroadcast.take();
+            }
+            transaction.progress = progress;
+        }
+
+        self
+    }
+
+    pub fn remove(&mut self, signature: &str) -> Option<PendingTransaction> {
+        self.transactions
+            .iter()
+            .position(|transaction| transaction.signature == signature)
+            .map(|index| self.transactions.remove(index))
+    }
+
+    pub fn clear_settled(&mut self) -> &mut Self {
+        self.transactions
+            .retain(|transaction| !transaction.progress.is_settled());
+
+        self
+    }
+}
+
+/// Records a transaction sent to the active cluster and polls its status
+pub fn track_transaction(signature: &str, description: &str) {
+    let cluster_name = CLUSTER_STORAGE.read().active_cluster().name().to_string();
+
+    track(PendingTransaction::new(
+        signature,
+        description,
+        &cluster_name,
+    ));
+}
+
+/// Like [track_transaction] but the transaction is also rebroadcast
+/// until the cluster sees it or reports it as expired
+pub fn track_rebroadcast(signature: &str, description: &str, rebroadcast: Rebroadcast) {
+    let cluster_name = CLUSTER_STORAGE.read().active_cluster().name().to_string();
+
+    track(
+        PendingTransaction::new(signature, description, &cluster_name).add_rebroadcast(rebroadcast),
+    );
+}
+
+fn track(transaction: PendingTransaction) {
+    PENDING_TRANSACTIONS.write().add(transaction);
+
+    poll_pending_transactions();
+}
+
+/// Starts polling unless a poller is already running. The poller
+/// stops once every transaction of the active cluster is settled.
+pub fn poll_pending_transactions() {
+    if PENDING_TRANSACTIONS.peek().polling {
+        return;
+    }
+    PENDING_TRANSACTIONS.write().polling = true;
+
+    // Not tied to the calling component so the poller
+    // survives the send modal being closed
+    spawn_forever(async move {
+        loop {
+            let cluster_name = CLUSTER_STORAGE.peek().active_cluster().name().to_string();
+            let signatures = PENDING_TRANSACTIONS
+                .peek()
+                .unsettled_signatures(&cluster_name);
+
+            if signatures.is_empty() {
+                break;
+            }
+
+            let client = RpcClient::new();
+            for signatures in signatures.chunks(MAX_SIGNATURES_PER_CALL) {
+                // Failed polls are retried on the next tick
+                let Ok(statuses) = client.get_signature_statuses(signatures, false).await else {
+                    continue;
+                };
+
+                let mut pending_transactions = PENDING_TRANSACTIONS.write();
+                signatures
+                    .iter()
+                    .zip(statuses)
+                    .for_each(|(signature, status)| {
+                        if let Some(status) = status {
+                            pending_transactions.set_progress(signature, (&status).into());
+                        }
+                    });
+            }
+
+            rebroadcast_unseen(&client, &cluster_name).await;
+
+            TimeoutFuture::new(POLL_INTERVAL_MS).await;
+        }
+
+        PENDING_TRANSACTIONS.write().polling = false;
+    });
+}
+
+/// Sends every transaction the cluster has not seen yet again, or marks
+/// it as expired once the block height passed its `last_valid_block_height`
+async fn rebroadcast_unseen<T: RpcTransport>(client: &RpcClient<T>, cluster_name: &str) {
+    let rebroadcasts = PENDING_TRANSACTIONS
+        .peek()
+        .unseen_rebroadcasts(cluster_name);
+    let without_rebroadcast = PENDING_TRANSACTIONS
+        .peek()
+        .has_unseen_without_rebroadcast(cluster_name);
+    if rebroadcasts.is_empty() && !without_rebroadcast {
+        return;
+    }
+
+    // The finalized height is behind the tip so a transaction is only
+    // reported as expired once it can no longer land in any fork
+    let Ok(block_height) = client.get_block_height(CommitmentConfig::default()).await else {
+        return;
+    };
+
+    if without_rebroadcast {
+        PENDING_TRANSACTIONS
+            .write()
+            .expire_unseen(cluster_name, block_height);
+    }
+
+    let config = SendTransactionConfig {
+        skip_preflight: Some(true),
+        max_retries: Some(0),
+        ..Default::default()
+    };
+
+    for (signature, rebroadcast) in rebroadcasts {
+        if rebroadcast.is_expired(block_height) {
+            PENDING_TRANSACTIONS
+                .write()
+                .set_progress(&signature, TransactionProgress::Expired);
+        } else if nonce_advanced(client, &rebroadcast).await {
+            // Landing advances the nonce as well, the transaction only
+            // expired if the cluster never saw it
+            let Ok(statuses) = client
+                .get_signature_statuses(std::slice::from_ref(&signature), true)
+                .await
+            else {
+                continue;
+            };
+
+            let progress = statuses
+                .first()
+                .and_then(Option::as_ref)
+                .map(TransactionProgress::from)
+                .unwrap_or(TransactionProgress::Expired);
+
+            PENDING_TRANSACTIONS
+                .write()
+                .set_progress(&signature, progress);
+        } else {
+            // A failed rebroadcast is retried on the next tick
+            client
+                .send_transaction(rebroadcast.wire_transaction(), config)
+                .await
+                .ok();
+        }
+    }
+}
+
+/// Whether the nonce of a durable `rebroadcast` is no longer the one it
+/// was signed with. Unknown nonces are checked again on the next tick.
+async fn nonce_advanced<T: RpcTransport>(client: &RpcClient<T>, rebroadcast: &Rebroadcast) -> bool {
+    let Some((address, nonce)) = rebroadcast.durable_nonce() else {
+        return false;
+    };
+
+    fetch_nonce_account(client, &address)
+        .await
+        .is_ok_and(|nonce_account| nonce_account.nonce != nonce)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expires_unseen_transactions_without_rebroadcast() {
+        let mut pending_transactions = PendingTransactions::default();
+        pending_transactions
+            .add(PendingTransaction::new("airdrop", "Airdrop", "devnet"))
+            .add(
+                PendingTransaction::new("send", "Send", "devnet")
+                    .add_rebroadcast(Rebroadcast::new("", 1_000)),
+            )
+            .add(PendingTransaction::new("other", "Airdrop", "testnet"));
+        assert!(pending_transactions.has_unseen_without_rebroadcast("devnet"));
+
+        // The first poll starts the validity window
+        pending_transactions.expire_unseen("devnet", 100);
+        pending_transactions.expire_unseen("devnet", 100 + MAX_PROCESSING_AGE);
+        assert_eq!(pending_transactions.unsettled_count(), 3);
+
+        pending_transactions.expire_unseen("devnet", 101 + MAX_PROCESSING_AGE);
+        let progress = |signature: &str| {
+            pending_transactions
+                .transactions()
+                .iter()
+                .find(|transaction| transaction.signature() == signature)
+                .map(|transaction| transaction.progress().clone())
+        };
+        assert_eq!(progress("airdrop"), Some(TransactionProgress::Expired));
+        // Rebroadcasts expire with their own blockhash
+        assert_eq!(progress("send"), Some(TransactionProgress::Submitted));
+        assert_eq!(progress("other"), Some(TransactionProgress::Submitted));
+        assert!(!pending_transactions.has_unseen_without_rebroadcast("devnet"));
+    }
+}
@@ -0,0 +1,439 @@
+use std::collections::HashMap;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    hash::Hash, instruction::Instruction, message::AddressLookupTableAccount, pubkey::Pubkey,
+    system_instruction::advance_nonce_account, transaction::VersionedTransaction,
+};
+use wallet_adapter::web_sys::Storage;
+
+use crate::{
+    is_advance_nonce, program_id, versioned_transaction, RpcClient, RpcError, RpcResult,
+    RpcTransport, SimulateTransactionConfig, TransactionLifetime, CLUSTER_STORAGE,
+    COMPUTE_BUDGET_PROGRAM_ID, PRIORITY_FEES, WINDOW,
+};
+
+/// `SetComputeUnitLimit` and `SetComputeUnitPrice` in the compute budget instruction enum
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// The most compute units a transaction can request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Added on top of the simulated compute units since
+/// the state can change before the transaction lands
+const COMPUTE_UNIT_MARGIN_PERCENT: u64 = 10;
+
+/// Key of the per cluster choices in the local storage of the browser
+const STORAGE_KEY: &str = "priority-fees";
+
+/// The price paid per compute unit to get a transaction scheduled sooner.
+/// Apart from [PriorityFee::Custom] the price follows the fees recently
+/// paid to write to the same accounts.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PriorityFee {
+    Low,
+    #[default]
+    Medium,
+    High,
+    /// Micro-lamports per compute unit
+    Custom(u64),
+}
+
+impl PriorityFee {
+    pub const LEVELS: [PriorityFee; 3] = [Self::Low, Self::Medium, Self::High];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::High => "High",
+            Self::Custom(_) => "Custom",
+        }
+    }
+
+    /// The price in micro-lamports per compute unit given the
+    /// prioritization fees of the recent slots
+    pub fn price(&self, recent_fees: &[PrioritizationFee]) -> u64 {
+        let percentile = match self {
+            Self::Low => 25,
+            Self::Medium => 50,
+            Self::High => 75,
+            Self::Custom(price) => return *price,
+        };
+
+        let mut fees = recent_fees
+            .iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect::<Vec<u64>>();
+        fees.sort_unstable();
+
+        fees.get((fees.len() * percentile / 100).min(fees.len().saturating_sub(1)))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// One entry of `getRecentPrioritizationFees`, the lowest fee
+/// paid by a transaction that landed in `slot`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrioritizationFee {
+    pub slot: u64,
+    pub prioritization_fee: u64,
+}
+
+/// The [PriorityFee] picked for each cluster, keyed by cluster name
+/// and saved to the local storage of the browser
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub(crate) struct PriorityFeeStore {
+    clusters: HashMap<String, PriorityFee>,
+}
+
+impl PriorityFeeStore {
+    /// Restores the choices of a previous session, an unreadable entry is ignored
+    pub fn load() -> Self {
+        let clusters = local_storage()
+            .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+            .and_then(|saved| serde_json::from_str(&saved).ok())
+            .unwrap_or_default();
+
+        Self { clusters }
+    }
+
+    pub fn get(&self, cluster_name: &str) -> PriorityFee {
+        self.clusters.get(cluster_name).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, cluster_name: &str, priority_fee: PriorityFee) -> &mut Self {
+        self.clusters.insert(cluster_name.to_string(), priority_fee);
+        self.save();
+
+        self
+    }
+
+    fn save(&self) {
+        if let (Some(storage), Ok(saved)) = (local_storage(), serde_json::to_string(&self.clusters))
+        {
+            // Still used for this session if the storage is full or disabled
+            let _ = storage.set_item(STORAGE_KEY, &saved);
+        }
+    }
+}
+
+fn local_storage() -> Option<Storage> {
+    WINDOW.read().local_storage().ok().flatten()
+}
+
+/// The [PriorityFee] picked for the active cluster
+pub(crate) fn active_priority_fee() -> PriorityFee {
+    PRIORITY_FEES
+        .read()
+        .get(CLUSTER_STORAGE.read().active_cluster().name())
+}
+
+/// Limits the compute units the transaction can consume
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_LIMIT];
+    data.extend_from_slice(&units.to_le_bytes());
+
+    Instruction::new_with_bytes(program_id(COMPUTE_BUDGET_PROGRAM_ID), &data, Vec::new())
+}
+
+/// Sets the price of a compute unit in micro-lamports
+pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_PRICE];
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+
+    Instruction::new_with_bytes(program_id(COMPUTE_BUDGET_PROGRAM_ID), &data, Vec::new())
+}
+
+/// The compute budget instructions prepended to every transaction
+pub fn compute_budget_instructions(units: u32, micro_lamports: u64) -> [Instruction; 2] {
+    [
+        set_compute_unit_limit(units),
+        set_compute_unit_price(micro_lamports),
+    ]
+}
+
+/// Prepends the compute budget instructions to `instructions`. The price
+/// follows `priority_fee` for the writable accounts and the limit is the
+/// simulated consumption plus a margin. A failing simulation keeps the
+/// maximum limit, the review of the transaction reports the failure.
+pub async fn with_compute_budget<T: RpcTransport>(
+    client: &RpcClient<T>,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+    priority_fee: PriorityFee,
+) -> RpcResult<Vec<Instruction>> {
+    let price = match priority_fee {
+        PriorityFee::Custom(price) => price,
+        priority_fee => {
+            let writable = writable_accounts(instructions, payer);
+
+            priority_fee.price(&client.get_recent_prioritization_fees(&writable).await?)
+        }
+    };
+
+    // A durable transaction has to start with the advance nonce instruction
+    let (advance_nonce, instructions) = match instructions.split_first() {
+        Some((first, rest)) if is_advance_nonce(first) => (Some(first), rest),
+        _ => (None, instructions),
+    };
+
+    let budgeted = |units: u32| {
+        advance_nonce
+            .cloned()
+            .into_iter()
+            .chain(compute_budget_instructions(units, price))
+            .chain(instructions.iter().cloned())
+            .collect::<Vec<Instruction>>()
+    };
+
+    // The node replaces the blockhash
+    let tx = versioned_transaction(
+        payer,
+        &budgeted(MAX_COMPUTE_UNIT_LIMIT),
+        lookup_tables,
+        Hash::default(),
+    )?;
+    let tx = bincode::serialize(&tx)
+        .map(|bytes| BASE64_STANDARD.encode(bytes))
+        .map_err(|error| RpcError::InvalidParams(error.to_string()))?;
+
+    let simulation = client
+        .simulate_transaction(
+            &tx,
+            SimulateTransactionConfig {
+                sig_verify: Some(false),
+                replace_recent_blockhash: Some(true),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    let units = match (simulation.err, simulation.units_consumed) {
+        (None, Some(units_consumed)) => compute_unit_limit(units_consumed),
+        _ => MAX_COMPUTE_UNIT_LIMIT,
+    };
+
+    Ok(budgeted(units))
+}
+
+/// Builds the v0 transaction of `instructions` with the compute budget
+/// of the [PriorityFee] picked for the active cluster and the blockhash
+/// of `lifetime`
+pub async fn budgeted_transaction<T: RpcTransport>(
+    client: &RpcClient<T>,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+    lifetime: &TransactionLifetime,
+) -> RpcResult<VersionedTransaction> {
+    let instructions = with_compute_budget(
+        client,
+        &lifetime.instructions(instructions),
+        payer,
+        lookup_tables,
+        active_priority_fee(),
+    )
+    .await?;
+
+    versioned_transaction(
+        payer,
+        &instructions,
+        lookup_tables,
+        lifetime.recent_blockhash(),
+    )
+}
+
+/// The size of the signed transaction once the compute budget and, for a
+/// durable transaction, the advance instruction of `nonce_account` are
+/// prepended. Signatures take the same space whether they are filled in or not.
+pub fn budgeted_size(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+    nonce_account: Option<&Pubkey>,
+) -> Option<usize> {
+    let instructions = nonce_account
+        .map(|nonce_account| advance_nonce_account(nonce_account, payer))
+        .into_iter()
+        .chain(compute_budget_instructions(
+            MAX_COMPUTE_UNIT_LIMIT,
+            u64::MAX,
+        ))
+        .chain(instructions.iter().cloned())
+        .collect::<Vec<Instruction>>();
+
+    versioned_transaction(payer, &instructions, lookup_tables, Hash::default())
+        .ok()
+        .and_then(|tx| bincode::serialized_size(&tx).ok())
+        .map(|size| size as usize)
+}
+
+/// The simulated consumption plus [COMPUTE_UNIT_MARGIN_PERCENT]
+fn compute_unit_limit(units_consumed: u64) -> u32 {
+    let units = units_consumed + units_consumed * COMPUTE_UNIT_MARGIN_PERCENT / 100;
+
+    units.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+}
+
+/// The accounts the transaction locks for writing, the fee payer first
+fn writable_accounts(instructions: &[Instruction], payer: &Pubkey) -> Vec<String> {
+    let mut writable = vec![payer.to_string()];
+
+    instructions
+        .iter()
+        .flat_map(|instruction| instruction.accounts.iter())
+        .filter(|account| account.is_writable)
+        .for_each(|account| {
+            let address = account.pubkey.to_string();
+
+            if !writable.contains(&address) {
+                writable.push(address);
+            }
+        });
+
+    writable
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use solana_sdk::system_instruction::transfer;
+
+    use super::*;
+    use crate::{block_on, MockTransport, RpcFixture};
+
+    fn recent_fees(fees: &[u64]) -> Vec<PrioritizationFee> {
+        fees.iter()
+            .enumerate()
+            .map(|(slot, fee)| PrioritizationFee {
+                slot: slot as u64,
+                prioritization_fee: *fee,
+            })
+            .collect()
+    }
+
+    fn client(
+        payer: &Pubkey,
+        recipient: &Pubkey,
+        tx: &VersionedTransaction,
+        simulation: Value,
+    ) -> RpcClient<MockTransport> {
+        RpcClient::with_transport(MockTransport::replay(vec![
+            RpcFixture {
+                method: "getRecentPrioritizationFees".to_string(),
+                params: json!([[payer.to_string(), recipient.to_string()]]),
+                response: json!({
+                    "jsonrpc": "2.0",
+                    "result": [
+                        { "slot": 1, "prioritizationFee": 0 },
+                        { "slot": 2, "prioritizationFee": 5000 },
+                        { "slot": 3, "prioritizationFee": 1000 },
+                        { "slot": 4, "prioritizationFee": 20000 }
+                    ]
+                }),
+            },
+            RpcFixture {
+                method: "simulateTransaction".to_string(),
+                params: json!([
+                    BASE64_STANDARD.encode(bincode::serialize(tx).unwrap()),
+                    {
+                        "sigVerify": false,
+                        "replaceRecentBlockhash": true,
+                        "encoding": "base64"
+                    }
+                ]),
+                response: json!({
+                    "jsonrpc": "2.0",
+                    "result": { "context": { "slot": 4 }, "value": simulation }
+                }),
+            },
+        ]))
+    }
+
+    #[test]
+    fn prices_follow_recent_fees() {
+        let fees = recent_fees(&[0, 0, 100, 200, 300, 400, 500, 10_000]);
+
+        assert_eq!(PriorityFee::Low.price(&fees), 100);
+        assert_eq!(PriorityFee::Medium.price(&fees), 300);
+        assert_eq!(PriorityFee::High.price(&fees), 500);
+        assert_eq!(PriorityFee::Custom(42).price(&fees), 42);
+        assert_eq!(PriorityFee::High.price(&[]), 0);
+
+        assert_eq!(
+            serde_json::to_string(&[PriorityFee::Low, PriorityFee::Custom(7)]).unwrap(),
+            r#"["low",{"custom":7}]"#
+        );
+    }
+
+    #[test]
+    fn limits_compute_units_to_the_simulation() {
+        let payer = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let instructions = [transfer(&payer, &recipient, 1_000)];
+
+        let simulated = versioned_transaction(
+            &payer,
+            &[
+                set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT),
+                set_compute_unit_price(5000),
+                instructions[0].clone(),
+            ],
+            &[],
+            Hash::default(),
+        )
+        .unwrap();
+
+        let succeeding = client(
+            &payer,
+            &recipient,
+            &simulated,
+            json!({ "err": null, "logs": [], "unitsConsumed": 450 }),
+        );
+        let budgeted = block_on(with_compute_budget(
+            &succeeding,
+            &instructions,
+            &payer,
+            &[],
+            PriorityFee::Medium,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            budgeted,
+            [
+                set_compute_unit_limit(495),
+                set_compute_unit_price(5000),
+                instructions[0].clone(),
+            ]
+        );
+        assert_eq!(budgeted[0].data, [2, 239, 1, 0, 0]);
+        assert_eq!(budgeted[1].data, [3, 136, 19, 0, 0, 0, 0, 0, 0]);
+
+        // A failing transaction keeps the maximum, the review shows why it fails
+        let failing = client(
+            &payer,
+            &recipient,
+            &simulated,
+            json!({ "err": "AccountNotFound", "logs": [], "unitsConsumed": 0 }),
+        );
+        let budgeted = block_on(with_compute_budget(
+            &failing,
+            &instructions,
+            &payer,
+            &[],
+            PriorityFee::Custom(5000),
+        ))
+        .unwrap();
+
+        assert_eq!(budgeted[0], set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT));
+    }
+}
@@ -0,0 +1,112 @@
+use std::{collections::HashMap, marker::PhantomData};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{RpcCall, RpcError, RpcRequest, RpcResponse, RpcResult};
+
+/// Several [RpcCall]s sent to the node as one JSON-RPC batch array
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct RpcBatch {
+    requests: Vec<RpcRequest<'static>>,
+}
+
+impl RpcBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues the call and returns the handle used to take
+    /// its result out of the [BatchResponse]
+    pub fn add<T: DeserializeOwned>(&mut self, call: RpcCall<T>) -> BatchCall<T> {
+        let request = call.into_request();
+        let id = request.id();
+        self.requests.push(request);
+
+        BatchCall {
+            id,
+            result: PhantomData,
+        }
+    }
+
+    pub fn requests(&self) -> &[RpcRequest<'static>] {
+        self.requests.as_slice()
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    pub fn to_json(&self) -> RpcResult<String> {
+        Ok(serde_json::to_string(&self.requests)?)
+    }
+}
+
+/// Identifies one call of a [RpcBatch] by its request id
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BatchCall<T> {
+    id: u64,
+    result: PhantomData<fn() -> T>,
+}
+
+impl<T> BatchCall<T> {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+/// The responses of a [RpcBatch] keyed by request id since
+/// nodes are free to answer batch members in any order
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct BatchResponse {
+    responses: HashMap<u64, RpcResponse<Value>>,
+}
+
+impl BatchResponse {
+    /// Parses the body of a batch request. A node that does not accept
+    /// batches answers with a single error object instead of an array,
+    /// that case is reported as [RpcError::BatchUnsupported].
+    pub fn parse(body: &str) -> RpcResult<Self> {
+        let value = serde_json::from_str::<Value>(body)
+            .map_err(|error| RpcError::BatchUnsupported(error.to_string()))?;
+
+        if !value.is_array() {
+            let reason = match serde_json::from_value::<RpcResponse<Value>>(value)?.into_result() {
+                Err(error) if error.is_transient() => return Err(error),
+                Err(error) => error.to_string(),
+                Ok(_) => "the node returned a single response".to_string(),
+            };
+
+            return Err(RpcError::BatchUnsupported(reason));
+        }
+
+        let mut batch_response = Self::default();
+        serde_json::from_value::<Vec<RpcResponse<Value>>>(value)?
+            .into_iter()
+            .for_each(|response| batch_response.insert(response));
+
+        Ok(batch_response)
+    }
+
+    pub fn insert(&mut self, response: RpcResponse<Value>) {
+        if let Some(id) = response.id {
+            self.responses.insert(id, response);
+        }
+    }
+
+    pub fn take<T: DeserializeOwned>(&mut self, call: BatchCall<T>) -> RpcResult<T> {
+        let response = self
+            .responses
+            .remove(&call.id)
+            .ok_or(RpcError::Deserialize(format!(
+                "The batch response has no entry for request id {}",
+                call.id
+            )))?;
+
+        Ok(serde_json::from_value::<T>(response.into_result()?)?)
+    }
+}
@@ -0,0 +1,198 @@
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::{
+    AccountInfoConfig, BlockHashResponseValue, CommitmentConfig, Encoding, PrioritizationFee,
+    ResponseWithContext, RpcRequest, RpcVersionInfo, SendTransactionConfig, SignatureStatus,
+    SignaturesForAddressConfig, SignaturesResponse, SimulateTransactionConfig, SimulationResult,
+    TokenAccountResponse, TokenAccountsFilter, TransactionConfig, TransactionResponse,
+};
+
+/// A single RPC method call and the type its `result` deserializes to.
+/// The same call can be sent on its own or as part of a [RpcBatch](crate::RpcBatch).
+#[derive(Debug, PartialEq, Clone)]
+pub struct RpcCall<T> {
+    method: &'static str,
+    params: Value,
+    result: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> RpcCall<T> {
+    pub fn new(method: &'static str, params: Value) -> Self {
+        Self {
+            method,
+            params,
+            result: PhantomData,
+        }
+    }
+
+    pub fn method(&self) -> &'static str {
+        self.method
+    }
+
+    pub fn into_request(self) -> RpcRequest<'static> {
+        RpcRequest::new(self.method, self.params)
+    }
+}
+
+impl RpcCall<RpcVersionInfo> {
+    pub fn get_version() -> Self {
+        Self::new("getVersion", json!([]))
+    }
+}
+
+impl RpcCall<ResponseWithContext<BlockHashResponseValue>> {
+    pub fn get_latest_blockhash(config: CommitmentConfig) -> Self {
+        Self::new("getLatestBlockhash", json!([config]))
+    }
+}
+
+impl RpcCall<ResponseWithContext<u64>> {
+    pub fn get_balance(address: &str, config: CommitmentConfig) -> Self {
+        Self::new("getBalance", json!([address, config]))
+    }
+}
+
+impl RpcCall<String> {
+    /// Resolves to the signature of the airdrop transaction
+    pub fn request_airdrop(address: &str, lamports: u64, config: CommitmentConfig) -> Self {
+        Self::new("requestAirdrop", json!([address, lamports, config]))
+    }
+
+    /// `transaction` is the base64 encoded signed transaction,
+    /// resolves to its first signature
+    pub fn send_transaction(transaction: &str, config: SendTransactionConfig) -> Self {
+        let config = SendTransactionConfig {
+            encoding: Some(Encoding::Base64),
+            ..config
+        };
+
+        Self::new("sendTransaction", json!([transaction, config]))
+    }
+}
+
+impl RpcCall<Option<TransactionResponse>> {
+    /// Requests the `jsonParsed` encoding and version 0 transactions since
+    /// [TransactionResponse] only understands parsed messages. Resolves to
+    /// `None` if the node does not know the transaction.
+    pub fn get_transaction(signature: &str, config: TransactionConfig) -> Self {
+        let config = TransactionConfig {
+            encoding: Some(Encoding::JsonParsed),
+            max_supported_transaction_version: config.max_supported_transaction_version.or(Some(0)),
+            ..config
+        };
+
+        Self::new("getTransaction", json!([signature, config]))
+    }
+}
+
+impl RpcCall<ResponseWithContext<SimulationResult>> {
+    /// `transaction` is the base64 encoded transaction, it does not need to be signed
+    pub fn simulate_transaction(transaction: &str, config: SimulateTransactionConfig) -> Self {
+        let config = SimulateTransactionConfig {
+            encoding: Some(Encoding::Base64),
+            ..config
+        };
+
+        Self::new("simulateTransaction", json!([transaction, config]))
+    }
+}
+
+impl RpcCall<ResponseWithContext<Option<u64>>> {
+    /// `message` is the base64 encoded message, resolves to `None`
+    /// once its blockhash expired
+    pub fn get_fee_for_message(message: &str, config: CommitmentConfig) -> Self {
+        Self::new("getFeeForMessage", json!([message, config]))
+    }
+}
+
+impl RpcCall<Vec<PrioritizationFee>> {
+    /// The fees of the recent slots for transactions writing to `addresses`,
+    /// nodes accept up to 128 addresses
+    pub fn get_recent_prioritization_fees(addresses: &[String]) -> Self {
+        Self::new("getRecentPrioritizationFees", json!([addresses]))
+    }
+}
+
+impl RpcCall<u64> {
+    pub fn get_block_height(config: CommitmentConfig) -> Self {
+        Self::new("getBlockHeight", json!([config]))
+    }
+
+    /// The lamports an account holding `data_length` bytes needs to be rent exempt
+    pub fn get_minimum_balance_for_rent_exemption(
+        data_length: usize,
+        config: CommitmentConfig,
+    ) -> Self {
+        Self::new(
+            "getMinimumBalanceForRentExemption",
+            json!([data_length, config]),
+        )
+    }
+}
+
+impl RpcCall<ResponseWithContext<Vec<TokenAccountResponse>>> {
+    /// The `jsonParsed` encoding is always requested since
+    /// [TokenAccountResponse] only understands parsed token data
+    pub fn get_token_accounts_by_owner(
+        owner: &str,
+        filter: TokenAccountsFilter,
+        config: AccountInfoConfig,
+    ) -> Self {
+        let config = AccountInfoConfig {
+            encoding: Some(Encoding::JsonParsed),
+            ..config
+        };
+
+        Self::new("getTokenAccountsByOwner", json!([owner, filter, config]))
+    }
+}
+
+impl<T: DeserializeOwned> RpcCall<ResponseWithContext<Option<T>>> {
+    /// Uses the `jsonParsed` encoding unless `config` sets one, `T` has
+    /// to match the encoding like for [RpcCall::get_multiple_accounts]
+    pub fn get_account_info(address: &str, config: AccountInfoConfig) -> Self {
+        let config = AccountInfoConfig {
+            encoding: config.encoding.or(Some(Encoding::JsonParsed)),
+            ..config
+        };
+
+        Self::new("getAccountInfo", json!([address, config]))
+    }
+}
+
+impl<T: DeserializeOwned> RpcCall<ResponseWithContext<Vec<Option<T>>>> {
+    /// Nodes accept up to 100 accounts per call. The `jsonParsed` encoding
+    /// is used unless `config` sets one, `T` has to match the encoding,
+    /// eg. [EncodedAccount](crate::EncodedAccount) for `base64`.
+    pub fn get_multiple_accounts(pubkeys: &[String], config: AccountInfoConfig) -> Self {
+        let config = AccountInfoConfig {
+            encoding: config.encoding.or(Some(Encoding::JsonParsed)),
+            ..config
+        };
+
+        Self::new("getMultipleAccounts", json!([pubkeys, config]))
+    }
+}
+
+impl RpcCall<Vec<SignaturesResponse>> {
+    pub fn get_signatures_for_address(address: &str, config: SignaturesForAddressConfig) -> Self {
+        Self::new("getSignaturesForAddress", json!([address, config]))
+    }
+}
+
+impl RpcCall<ResponseWithContext<Vec<Option<SignatureStatus>>>> {
+    /// Nodes accept up to 256 signatures per call. Without
+    /// `search_transaction_history` only recent signatures are found.
+    pub fn get_signature_statuses(signatures: &[String], search_transaction_history: bool) -> Self {
+        Self::new(
+            "getSignatureStatuses",
+            json!([
+                signatures,
+                { "searchTransactionHistory": search_transaction_history }
+            ]),
+        )
+    }
+}
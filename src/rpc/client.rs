@@ -0,0 +1,277 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    AccountInfoConfig, BatchResponse, BlockHashResponseValue, BrowserTransport, CancelHandle,
+    CommitmentConfig, PrioritizationFee, RpcBatch, RpcCall, RpcError, RpcResponse, RpcResult,
+    RpcTransport, SendTransactionConfig, SignatureStatus, SignaturesForAddressConfig,
+    SignaturesResponse, SimulateTransactionConfig, SimulationResult, TokenAccountResponse,
+    TokenAccountsFilter, TransactionConfig, TransactionResponse,
+};
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A JSON-RPC 2.0 request body. Every request gets a new id.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct RpcRequest<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: Value,
+}
+
+impl<'a> RpcRequest<'a> {
+    pub fn new(method: &'a str, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed),
+            method,
+            params,
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn method(&self) -> &str {
+        self.method
+    }
+
+    pub fn to_json(&self) -> RpcResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+pub struct RpcVersionInfo {
+    #[serde(rename = "solana-core")]
+    pub solana_core: String,
+    #[serde(rename = "feature-set")]
+    pub feature_set: Option<u32>,
+}
+
+/// Typed Solana JSON-RPC client. [RpcClient::new] sends requests to the
+/// active cluster with the browser `fetch` api, any other [RpcTransport]
+/// can be plugged in with [RpcClient::with_transport].
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct RpcClient<T = BrowserTransport> {
+    transport: T,
+}
+
+impl RpcClient {
+    pub fn new() -> Self {
+        Self::with_transport(BrowserTransport::new())
+    }
+
+    /// Like [RpcClient::new] but every request can be aborted with `cancel_handle`
+    pub fn with_cancel_handle(cancel_handle: CancelHandle) -> Self {
+        Self::with_transport(BrowserTransport::new().add_cancel_handle(cancel_handle))
+    }
+}
+
+impl<T: RpcTransport> RpcClient<T> {
+    pub fn with_transport(transport: T) -> Self {
+        Self { transport }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Returns the `result` of the response or the [RpcError]
+    /// built from its `error` object
+    pub async fn call<R: DeserializeOwned>(&self, call: RpcCall<R>) -> RpcResult<R> {
+        let body = call.into_request().to_json()?;
+
+        let response_body = self.transport.send(&body).await?;
+
+        serde_json::from_str::<RpcResponse<R>>(&response_body)?.into_result()
+    }
+
+    /// Sends a method this client has no typed helper for
+    pub async fn send<R: DeserializeOwned>(
+        &self,
+        method: &'static str,
+        params: Value,
+    ) -> RpcResult<R> {
+        self.call(RpcCall::new(method, params)).await
+    }
+
+    /// Sends all calls of the batch in a single request. If the node
+    /// rejects batches the calls are sent one after the other instead.
+    pub async fn send_batch(&self, batch: RpcBatch) -> RpcResult<BatchResponse> {
+        let response_body = self.transport.send(&batch.to_json()?).await?;
+
+        match BatchResponse::parse(&response_body) {
+            Err(RpcError::BatchUnsupported(_)) => self.send_sequential(batch).await,
+            parsed => parsed,
+        }
+    }
+
+    async fn send_sequential(&self, batch: RpcBatch) -> RpcResult<BatchResponse> {
+        let mut batch_response = BatchResponse::default();
+
+        for request in batch.requests() {
+            let response_body = self.transport.send(&request.to_json()?).await?;
+
+            batch_response.insert(serde_json::from_str::<RpcResponse<Value>>(&response_body)?);
+        }
+
+        Ok(batch_response)
+    }
+
+    pub async fn get_version(&self) -> RpcResult<RpcVersionInfo> {
+        self.call(RpcCall::get_version()).await
+    }
+
+    pub async fn get_latest_blockhash(
+        &self,
+        config: CommitmentConfig,
+    ) -> RpcResult<BlockHashResponseValue> {
+        self.call(RpcCall::get_latest_blockhash(config))
+            .await
+            .map(|response| response.value)
+    }
+
+    pub async fn get_balance(&self, address: &str, config: CommitmentConfig) -> RpcResult<u64> {
+        self.call(RpcCall::get_balance(address, config))
+            .await
+            .map(|response| response.value)
+    }
+
+    /// Returns the signature of the airdrop transaction
+    pub async fn request_airdrop(
+        &self,
+        address: &str,
+        lamports: u64,
+        config: CommitmentConfig,
+    ) -> RpcResult<String> {
+        self.call(RpcCall::request_airdrop(address, lamports, config))
+            .await
+    }
+
+    /// Sends the base64 encoded signed `transaction`, returns its signature
+    pub async fn send_transaction(
+        &self,
+        transaction: &str,
+        config: SendTransactionConfig,
+    ) -> RpcResult<String> {
+        self.call(RpcCall::send_transaction(transaction, config))
+            .await
+    }
+
+    /// Runs the base64 encoded `transaction` without committing it
+    pub async fn simulate_transaction(
+        &self,
+        transaction: &str,
+        config: SimulateTransactionConfig,
+    ) -> RpcResult<SimulationResult> {
+        self.call(RpcCall::simulate_transaction(transaction, config))
+            .await
+            .map(|response| response.value)
+    }
+
+    /// The fee of the base64 encoded `message`, `None` once its blockhash expired
+    pub async fn get_fee_for_message(
+        &self,
+        message: &str,
+        config: CommitmentConfig,
+    ) -> RpcResult<Option<u64>> {
+        self.call(RpcCall::get_fee_for_message(message, config))
+            .await
+            .map(|response| response.value)
+    }
+
+    /// The prioritization fees of the recent slots for transactions writing to `addresses`
+    pub async fn get_recent_prioritization_fees(
+        &self,
+        addresses: &[String],
+    ) -> RpcResult<Vec<PrioritizationFee>> {
+        self.call(RpcCall::get_recent_prioritization_fees(addresses))
+            .await
+    }
+
+    pub async fn get_block_height(&self, config: CommitmentConfig) -> RpcResult<u64> {
+        self.call(RpcCall::get_block_height(config)).await
+    }
+
+    /// The lamports an account holding `data_length` bytes needs to be rent exempt
+    pub async fn get_minimum_balance_for_rent_exemption(
+        &self,
+        data_length: usize,
+        config: CommitmentConfig,
+    ) -> RpcResult<u64> {
+        self.call(RpcCall::get_minimum_balance_for_rent_exemption(
+            data_length,
+            config,
+        ))
+        .await
+    }
+
+    pub async fn get_transaction(
+        &self,
+        signature: &str,
+        config: TransactionConfig,
+    ) -> RpcResult<Option<TransactionResponse>> {
+        self.call(RpcCall::get_transaction(signature, config)).await
+    }
+
+    pub async fn get_token_accounts_by_owner(
+        &self,
+        owner: &str,
+        filter: TokenAccountsFilter,
+        config: AccountInfoConfig,
+    ) -> RpcResult<Vec<TokenAccountResponse>> {
+        self.call(RpcCall::get_token_accounts_by_owner(owner, filter, config))
+            .await
+            .map(|response| response.value)
+    }
+
+    /// `None` if the account does not exist
+    pub async fn get_account_info<R: DeserializeOwned>(
+        &self,
+        address: &str,
+        config: AccountInfoConfig,
+    ) -> RpcResult<Option<R>> {
+        self.call(RpcCall::get_account_info(address, config))
+            .await
+            .map(|response| response.value)
+    }
+
+    /// The parsed accounts in the order of `pubkeys`, `None` for missing accounts
+    pub async fn get_multiple_accounts<R: DeserializeOwned>(
+        &self,
+        pubkeys: &[String],
+        config: AccountInfoConfig,
+    ) -> RpcResult<Vec<Option<R>>> {
+        self.call(RpcCall::get_multiple_accounts(pubkeys, config))
+            .await
+            .map(|response| response.value)
+    }
+
+    pub async fn get_signatures_for_address(
+        &self,
+        address: &str,
+        config: SignaturesForAddressConfig,
+    ) -> RpcResult<Vec<SignaturesResponse>> {
+        self.call(RpcCall::get_signatures_for_address(address, config))
+            .await
+    }
+
+    /// The statuses in the order of `signatures`, `None` for unknown signatures
+    pub async fn get_signature_statuses(
+        &self,
+        signatures: &[String],
+        search_transaction_history: bool,
+    ) -> RpcResult<Vec<Option<SignatureStatus>>> {
+        self.call(RpcCall::get_signature_statuses(
+            signatures,
+            search_transaction_history,
+        ))
+        .await
+        .map(|response| response.value)
+    }
+}
@@ -0,0 +1,277 @@
+use serde::Deserialize;
+use serde_json::Value;
+use wallet_adapter::WalletError;
+
+pub type RpcResult<T> = Result<T, RpcError>;
+
+/// The `error` member of a JSON-RPC response
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+pub struct RpcErrorObject {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<Value>,
+}
+
+/// Errors returned by the RPC layer. The JSON-RPC error codes documented
+/// by Solana each get their own variant holding the message from the node.
+#[derive(Debug, PartialEq, Clone)]
+pub enum RpcError {
+    ParseError(String),
+    InvalidRequest(String),
+    MethodNotFound(String),
+    InvalidParams(String),
+    InternalError(String),
+    BlockCleanedUp(String),
+    SendTransactionPreflightFailure {
+        message: String,
+        logs: Vec<String>,
+    },
+    TransactionSignatureVerificationFailure(String),
+    BlockNotAvailable(String),
+    NodeUnhealthy {
+        message: String,
+        num_slots_behind: Option<u64>,
+    },
+    TransactionPrecompileVerificationFailure(String),
+    SlotSkipped(String),
+    NoSnapshot(String),
+    LongTermStorageSlotSkipped(String),
+    KeyExcludedFromSecondaryIndex(String),
+    TransactionHistoryNotAvailable(String),
+    ScanError(String),
+    TransactionSignatureLenMismatch(String),
+    BlockStatusNotAvailableYet(String),
+    UnsupportedTransactionVersion(String),
+    MinContextSlotNotReached(String),
+    EpochRewardsPeriodActive(String),
+    RateLimited(String),
+    /// Any other error code returned by the node
+    Server {
+        code: i64,
+        message: String,
+    },
+    /// The request never got a JSON-RPC response, eg. the fetch failed
+    Transport(String),
+    /// No endpoint answered within the timeout in milliseconds
+    Timeout(u32),
+    /// The request was aborted with a [CancelHandle](crate::CancelHandle)
+    Cancelled,
+    /// The response body is not the expected JSON
+    Deserialize(String),
+    /// The node answered a batch request with something other than an array
+    BatchUnsupported(String),
+    /// The request could not be built locally, eg. a message failed to compile
+    Build(String),
+}
+
+impl RpcError {
+    pub fn code(&self) -> Option<i64> {
+        let code = match self {
+            Self::ParseError(_) => -32700,
+            Self::InvalidRequest(_) => -32600,
+            Self::MethodNotFound(_) => -32601,
+            Self::InvalidParams(_) => -32602,
+            Self::InternalError(_) => -32603,
+            Self::BlockCleanedUp(_) => -32001,
+            Self::SendTransactionPreflightFailure { .. } => -32002,
+            Self::TransactionSignatureVerificationFailure(_) => -32003,
+            Self::BlockNotAvailable(_) => -32004,
+            Self::NodeUnhealthy { .. } => -32005,
+            Self::TransactionPrecompileVerificationFailure(_) => -32006,
+            Self::SlotSkipped(_) => -32007,
+            Self::NoSnapshot(_) => -32008,
+            Self::LongTermStorageSlotSkipped(_) => -32009,
+            Self::KeyExcludedFromSecondaryIndex(_) => -32010,
+            Self::TransactionHistoryNotAvailable(_) => -32011,
+            Self::ScanError(_) => -32012,
+            Self::TransactionSignatureLenMismatch(_) => -32013,
+            Self::BlockStatusNotAvailableYet(_) => -32014,
+            Self::UnsupportedTransactionVersion(_) => -32015,
+            Self::MinContextSlotNotReached(_) => -32016,
+            Self::EpochRewardsPeriodActive(_) => -32017,
+            Self::RateLimited(_) => 429,
+            Self::Server { code, .. } => *code,
+            Self::Transport(_)
+            | Self::Timeout(_)
+            | Self::Cancelled
+            | Self::Deserialize(_)
+            | Self::BatchUnsupported(_)
+            | Self::Build(_) => return None,
+        };
+
+        Some(code)
+    }
+
+    /// Errors worth retrying later since the node may answer correctly
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::NodeUnhealthy { .. }
+                | Self::BlockStatusNotAvailableYet(_)
+                | Self::MinContextSlotNotReached(_)
+                | Self::RateLimited(_)
+                | Self::Transport(_)
+                | Self::Timeout(_)
+        )
+    }
+}
+
+impl From<RpcErrorObject> for RpcError {
+    fn from(error: RpcErrorObject) -> Self {
+        let RpcErrorObject {
+            code,
+            message,
+            data,
+        } = error;
+
+        match code {
+            -32700 => Self::ParseError(message),
+            -32600 => Self::InvalidRequest(message),
+            -32601 => Self::MethodNotFound(message),
+            -32602 => Self::InvalidParams(message),
+            -32603 => Self::InternalError(message),
+            -32001 => Self::BlockCleanedUp(message),
+            -32002 => {
+                let logs = data
+                    .as_ref()
+                    .and_then(|data| data.get("logs"))
+                    .and_then(|logs| serde_json::from_value::<Vec<String>>(logs.clone()).ok())
+                    .unwrap_or_default();
+
+                Self::SendTransactionPreflightFailure { message, logs }
+            }
+            -32003 => Self::TransactionSignatureVerificationFailure(message),
+            -32004 => Self::BlockNotAvailable(message),
+            -32005 => {
+                let num_slots_behind = data
+                    .as_ref()
+                    .and_then(|data| data.get("numSlotsBehind"))
+                    .and_then(Value::as_u64);
+
+                Self::NodeUnhealthy {
+                    message,
+                    num_slots_behind,
+                }
+            }
+            -32006 => Self::TransactionPrecompileVerificationFailure(message),
+            -32007 => Self::SlotSkipped(message),
+            -32008 => Self::NoSnapshot(message),
+            -32009 => Self::LongTermStorageSlotSkipped(message),
+            -32010 => Self::KeyExcludedFromSecondaryIndex(message),
+            -32011 => Self::TransactionHistoryNotAvailable(message),
+            -32012 => Self::ScanError(message),
+            -32013 => Self::TransactionSignatureLenMismatch(message),
+            -32014 => Self::BlockStatusNotAvailableYet(message),
+            -32015 => Self::UnsupportedTransactionVersion(message),
+            -32016 => Self::MinContextSlotNotReached(message),
+            -32017 => Self::EpochRewardsPeriodActive(message),
+            // Rate limits are not part of the spec and providers
+            // report them with either of these codes
+            429 | -32429 => Self::RateLimited(message),
+            _ => Self::Server { code, message },
+        }
+    }
+}
+
+impl From<WalletError> for RpcError {
+    fn from(error: WalletError) -> Self {
+        Self::Transport(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for RpcError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Deserialize(error.to_string())
+    }
+}
+
+impl From<RpcError> for WalletError {
+    fn from(error: RpcError) -> Self {
+        WalletError::Op(error.to_string())
+    }
+}
+
+impl core::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ParseError(message) => {
+                write!(f, "The node could not parse the request: {message}")
+            }
+            Self::InvalidRequest(message) => write!(f, "Invalid request: {message}"),
+            Self::MethodNotFound(message) => {
+                write!(f, "The node does not support this method: {message}")
+            }
+            Self::InvalidParams(message) => write!(f, "Invalid parameters: {message}"),
+            Self::InternalError(message) => write!(f, "Internal node error: {message}"),
+            Self::BlockCleanedUp(message) => {
+                write!(f, "The block is no longer stored by the node: {message}")
+            }
+            Self::SendTransactionPreflightFailure { message, .. } => {
+                write!(f, "Transaction simulation failed: {message}")
+            }
+            Self::TransactionSignatureVerificationFailure(message) => {
+                write!(f, "Transaction signature verification failed: {message}")
+            }
+            Self::BlockNotAvailable(message) => write!(f, "Block not available: {message}"),
+            Self::NodeUnhealthy {
+                num_slots_behind: Some(slots),
+                ..
+            } => write!(f, "The node is unhealthy, it is {slots} slots behind"),
+            Self::NodeUnhealthy { message, .. } => write!(f, "The node is unhealthy: {message}"),
+            Self::TransactionPrecompileVerificationFailure(message) => {
+                write!(f, "Transaction precompile verification failed: {message}")
+            }
+            Self::SlotSkipped(message) => write!(f, "The slot was skipped: {message}"),
+            Self::NoSnapshot(message) => write!(f, "The node has no snapshot: {message}"),
+            Self::LongTermStorageSlotSkipped(message) => {
+                write!(
+                    f,
+                    "The slot was skipped or is missing in long-term storage: {message}"
+                )
+            }
+            Self::KeyExcludedFromSecondaryIndex(message) => {
+                write!(
+                    f,
+                    "The key is excluded from the node's secondary index: {message}"
+                )
+            }
+            Self::TransactionHistoryNotAvailable(message) => {
+                write!(f, "The node does not keep transaction history: {message}")
+            }
+            Self::ScanError(message) => write!(f, "The node failed to scan accounts: {message}"),
+            Self::TransactionSignatureLenMismatch(message) => {
+                write!(f, "Transaction signature count mismatch: {message}")
+            }
+            Self::BlockStatusNotAvailableYet(message) => {
+                write!(f, "The block status is not available yet: {message}")
+            }
+            Self::UnsupportedTransactionVersion(message) => {
+                write!(f, "Unsupported transaction version: {message}")
+            }
+            Self::MinContextSlotNotReached(message) => {
+                write!(
+                    f,
+                    "The node has not reached the minimum context slot: {message}"
+                )
+            }
+            Self::EpochRewardsPeriodActive(message) => {
+                write!(f, "Epoch rewards are being paid out: {message}")
+            }
+            Self::RateLimited(message) => write!(f, "Rate limited by the node: {message}"),
+            Self::Server { code, message } => write!(f, "RPC error {code}: {message}"),
+            Self::Transport(message) => write!(f, "Could not reach the node: {message}"),
+            Self::Timeout(timeout_ms) => {
+                write!(f, "The node did not answer within {timeout_ms} ms")
+            }
+            Self::Cancelled => write!(f, "The request was cancelled"),
+            Self::Deserialize(message) => write!(f, "Invalid response from the node: {message}"),
+            Self::BatchUnsupported(message) => {
+                write!(f, "The node does not accept batch requests: {message}")
+            }
+            Self::Build(message) => write!(f, "Could not build the transaction: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for RpcError {}
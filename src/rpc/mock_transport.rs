@@ -0,0 +1,304 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    future::Future,
+    path::Path,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    NativeTransport, RpcError, RpcResult, RpcTransport, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+
+/// The wallet `fixtures/rpc/accounts.json` was recorded for
+pub const OWNER: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+pub const RECIPIENT: &str = "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T";
+pub const MINT: &str = "Gh9ZwEmdLJ8DscKNTkTqPbNwLNNBjuSzaG9Vp2KGtKJr";
+/// The [OWNER]'s associated token account of [MINT]
+pub const SOURCE: &str = "7UX2i7SucgLMQcfZ75s3VXmZZY4YRUyJN9X1RgfMoDUi";
+
+/// A recorded JSON-RPC response for one method called with one set of params.
+/// `response` is the full response object, its `id` is replaced on replay.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RpcFixture {
+    pub method: String,
+    pub params: Value,
+    pub response: Value,
+}
+
+impl RpcFixture {
+    fn key(method: &str, params: &Value) -> String {
+        // `serde_json` sorts object keys so equal params always give the same key
+        String::new() + method + " " + &params.to_string()
+    }
+}
+
+/// [RpcTransport] that answers requests from [RpcFixture]s instead of a node.
+/// In record mode requests are forwarded to another transport and every
+/// response is captured so it can be saved as a fixture file.
+#[derive(Debug)]
+pub struct MockTransport<T = NativeTransport> {
+    fixtures: RefCell<BTreeMap<String, RpcFixture>>,
+    recorder: Option<T>,
+    reject_batches: bool,
+}
+
+impl MockTransport {
+    pub fn replay(fixtures: Vec<RpcFixture>) -> Self {
+        let mock = Self {
+            fixtures: RefCell::default(),
+            recorder: None,
+            reject_batches: false,
+        };
+        fixtures
+            .into_iter()
+            .for_each(|fixture| mock.insert(fixture));
+
+        mock
+    }
+
+    /// Parses a fixture file, a JSON array of [RpcFixture]s
+    pub fn from_json(fixtures: &str) -> RpcResult<Self> {
+        Ok(Self::replay(serde_json::from_str(fixtures)?))
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> RpcResult<Self> {
+        let fixtures = std::fs::read_to_string(path)
+            .map_err(|error| RpcError::Transport(error.to_string()))?;
+
+        Self::from_json(&fixtures)
+    }
+}
+
+impl<T: RpcTransport> MockTransport<T> {
+    /// Forwards every request to `transport` and records the responses
+    pub fn record(transport: T) -> Self {
+        Self {
+            fixtures: RefCell::default(),
+            recorder: Some(transport),
+            reject_batches: false,
+        }
+    }
+
+    /// Answers batch requests the way nodes with batching disabled do
+    pub fn reject_batches(mut self) -> Self {
+        self.reject_batches = true;
+
+        self
+    }
+
+    pub fn insert(&self, fixture: RpcFixture) {
+        let key = RpcFixture::key(&fixture.method, &fixture.params);
+        self.fixtures.borrow_mut().insert(key, fixture);
+    }
+
+    pub fn fixtures(&self) -> Vec<RpcFixture> {
+        self.fixtures.borrow().values().cloned().collect()
+    }
+
+    /// Writes the recorded fixtures in the format [MockTransport::from_file] reads
+    pub fn save(&self, path: impl AsRef<Path>) -> RpcResult<()> {
+        let fixtures = serde_json::to_string_pretty(&self.fixtures())?;
+
+        std::fs::write(path, fixtures).map_err(|error| RpcError::Transport(error.to_string()))
+    }
+
+    fn replay_request(&self, request: &Map<String, Value>) -> RpcResult<Value> {
+        let method = request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let mut response = self
+            .fixtures
+            .borrow()
+            .get(&RpcFixture::key(method, &params))
+            .map(|fixture| fixture.response.clone())
+            .ok_or(RpcError::Transport(format!(
+                "No fixture for `{method}` with params `{params}`"
+            )))?;
+
+        if let Some(response) = response.as_object_mut() {
+            response.insert(
+                "id".to_string(),
+                request.get("id").cloned().unwrap_or(Value::Null),
+            );
+        }
+
+        Ok(response)
+    }
+
+    fn record_response(&self, request: &Map<String, Value>, response: &Value) {
+        let mut response = response.clone();
+        if let Some(response) = response.as_object_mut() {
+            response.remove("id");
+        }
+
+        self.insert(RpcFixture {
+            method: request
+                .get("method")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            params: request.get("params").cloned().unwrap_or(Value::Null),
+            response,
+        });
+    }
+}
+
+impl<T: RpcTransport> RpcTransport for MockTransport<T> {
+    async fn send(&self, body: &str) -> RpcResult<String> {
+        let body = serde_json::from_str::<Value>(body)?;
+
+        let requests =
+            match &body {
+                Value::Array(requests) if self.reject_batches => return Ok(json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": {
+                        "code": -32600,
+                        "message": format!("batch of {} requests is not supported", requests.len()),
+                    }
+                })
+                .to_string()),
+                Value::Array(requests) => requests.iter().collect::<Vec<_>>(),
+                request => vec![request],
+            };
+        let requests = requests
+            .into_iter()
+            .map(|request| {
+                request
+                    .as_object()
+                    .ok_or(RpcError::InvalidRequest(request.to_string()))
+            })
+            .collect::<RpcResult<Vec<_>>>()?;
+
+        if let Some(recorder) = self.recorder.as_ref() {
+            let response_body = recorder.send(&body.to_string()).await?;
+
+            let responses = match serde_json::from_str::<Value>(&response_body)? {
+                Value::Array(responses) => responses,
+                response => vec![response],
+            };
+
+            // Batch responses can come back in any order so match them by id
+            requests.iter().for_each(|request| {
+                if let Some(response) = responses
+                    .iter()
+                    .find(|response| response.get("id") == request.get("id"))
+                {
+                    self.record_response(request, response);
+                }
+            });
+
+            return Ok(response_body);
+        }
+
+        let responses = requests
+            .iter()
+            .map(|request| self.replay_request(request))
+            .collect::<RpcResult<Vec<_>>>()?;
+
+        let response = if body.is_array() {
+            Value::Array(responses)
+        } else {
+            responses.into_iter().next().unwrap_or_default()
+        };
+
+        Ok(response.to_string())
+    }
+}
+
+/// Runs a future against a [MockTransport] to completion.
+/// `MockTransport` never waits so every future is ready on the first poll.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("The mock transport should never be pending"),
+    }
+}
+
+/// An account as `getAccountInfo` returns it with the `base64` encoding
+pub fn encoded_account(owner: &str, lamports: u64, data: &[u8]) -> Value {
+    json!({
+        "data": [BASE64_STANDARD.encode(data), "base64"],
+        "executable": false,
+        "lamports": lamports,
+        "owner": owner,
+        "rentEpoch": u64::MAX,
+        "space": data.len()
+    })
+}
+
+/// A wallet account holding only lamports
+pub fn system_account(lamports: u64) -> Value {
+    encoded_account(SYSTEM_PROGRAM_ID, lamports, &[])
+}
+
+/// An initialized token account of [MINT] as the `jsonParsed` encoding returns it
+pub fn token_account(owner: &str, amount: u64) -> Value {
+    json!({
+        "data": {
+            "parsed": {
+                "info": {
+                    "mint": MINT,
+                    "owner": owner,
+                    "state": "initialized",
+                    "tokenAmount": {
+                        "amount": amount.to_string(),
+                        "decimals": 6,
+                        "uiAmount": null,
+                        "uiAmountString": ""
+                    }
+                },
+                "type": "account"
+            },
+            "program": "spl-token",
+            "space": 165
+        },
+        "executable": false,
+        "lamports": 2039280,
+        "owner": TOKEN_PROGRAM_ID,
+        "rentEpoch": u64::MAX,
+        "space": 165
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNTS_FIXTURES: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/rpc/accounts.json");
+
+    /// Sends the requests of `fixtures/rpc/accounts.json` to a node again and
+    /// overwrites the file with its responses, run it with
+    /// `RPC_ENDPOINT=<url> cargo test record_account_fixtures -- --ignored`
+    #[test]
+    #[ignore = "records fixtures from the node at `RPC_ENDPOINT`"]
+    fn record_account_fixtures() {
+        let endpoint = std::env::var("RPC_ENDPOINT").expect("`RPC_ENDPOINT` is not set");
+        let recorder = MockTransport::record(NativeTransport::new(&endpoint));
+
+        let requests = MockTransport::from_file(ACCOUNTS_FIXTURES)
+            .unwrap()
+            .fixtures();
+        requests.into_iter().enumerate().for_each(|(id, fixture)| {
+            let body = json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": fixture.method,
+                "params": fixture.params,
+            });
+            block_on(recorder.send(&body.to_string())).unwrap();
+        });
+
+        recorder.save(ACCOUNTS_FIXTURES).unwrap();
+    }
+}
@@ -0,0 +1,25 @@
+mod error;
+pub use error::*;
+
+mod types;
+pub use types::*;
+
+mod transport;
+pub use transport::*;
+
+#[cfg(test)]
+mod mock_transport;
+#[cfg(test)]
+pub use mock_transport::*;
+
+mod client;
+pub use client::*;
+
+mod call;
+pub use call::*;
+
+mod batch;
+pub use batch::*;
+
+mod pubsub;
+pub use pubsub::*;
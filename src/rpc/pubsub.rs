@@ -0,0 +1,435 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use gloo_timers::callback::Timeout;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_transaction_error::TransactionError;
+use wallet_adapter::web_sys::{
+    wasm_bindgen::{closure::Closure, JsCast},
+    CloseEvent, MessageEvent, WebSocket,
+};
+
+use crate::{
+    AccountInfoConfig, Commitment, CommitmentConfig, Encoding, ResponseWithContext, RpcRequest,
+};
+
+/// Delay before the first reconnect, doubled on every failed attempt
+const RECONNECT_DELAY_MS: u32 = 1000;
+const MAX_RECONNECT_DELAY_MS: u32 = 30_000;
+
+/// A PubSub subscription. Subscriptions are remembered by the
+/// [PubSubClient] and sent again every time the socket reconnects.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum Subscription {
+    Account(String),
+    Signature(String),
+    Slot,
+}
+
+impl Subscription {
+    fn subscribe_request(&self) -> RpcRequest<'static> {
+        match self {
+            Self::Account(pubkey) => RpcRequest::new(
+                "accountSubscribe",
+                json!([
+                    pubkey,
+                    AccountInfoConfig {
+                        commitment: Some(Commitment::Confirmed),
+                        encoding: Some(Encoding::Base64),
+                        ..Default::default()
+                    }
+                ]),
+            ),
+            Self::Signature(signature) => RpcRequest::new(
+                "signatureSubscribe",
+                json!([signature, CommitmentConfig::new(Commitment::Confirmed)]),
+            ),
+            Self::Slot => RpcRequest::new("slotSubscribe", json!([])),
+        }
+    }
+
+    fn unsubscribe_method(&self) -> &'static str {
+        match self {
+            Self::Account(_) => "accountUnsubscribe",
+            Self::Signature(_) => "signatureUnsubscribe",
+            Self::Slot => "slotUnsubscribe",
+        }
+    }
+}
+
+/// A decoded `*Notification` message
+#[derive(Debug, PartialEq, Clone)]
+pub enum PubSubNotification {
+    Account {
+        pubkey: String,
+        lamports: u64,
+        slot: u64,
+    },
+    Signature {
+        signature: String,
+        err: Option<TransactionError>,
+        slot: u64,
+    },
+    Slot {
+        slot: u64,
+        parent: u64,
+        root: u64,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct NotificationMessage {
+    params: NotificationParams,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotificationParams {
+    result: Value,
+    subscription: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountNotification {
+    lamports: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignatureNotification {
+    err: Option<TransactionError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlotNotification {
+    slot: u64,
+    parent: u64,
+    root: u64,
+}
+
+impl PubSubNotification {
+    fn parse(subscription: &Subscription, result: Value) -> Option<Self> {
+        let notification = match subscription {
+            Subscription::Account(pubkey) => {
+                let account =
+                    serde_json::from_value::<ResponseWithContext<AccountNotification>>(result)
+                        .ok()?;
+
+                Self::Account {
+                    pubkey: pubkey.clone(),
+                    lamports: account.value.lamports,
+                    slot: account.context.slot,
+                }
+            }
+            Subscription::Signature(signature) => {
+                let status =
+                    serde_json::from_value::<ResponseWithContext<SignatureNotification>>(result)
+                        .ok()?;
+
+                Self::Signature {
+                    signature: signature.clone(),
+                    err: status.value.err,
+                    slot: status.context.slot,
+                }
+            }
+            Subscription::Slot => {
+                let slot = serde_json::from_value::<SlotNotification>(result).ok()?;
+
+                Self::Slot {
+                    slot: slot.slot,
+                    parent: slot.parent,
+                    root: slot.root,
+                }
+            }
+        };
+
+        Some(notification)
+    }
+}
+
+struct PubSubState {
+    endpoint: String,
+    socket: Option<WebSocket>,
+    subscriptions: Vec<Subscription>,
+    /// Subscribe requests waiting for their subscription id, keyed by request id
+    pending: HashMap<u64, Subscription>,
+    /// Confirmed subscriptions keyed by subscription id
+    active: HashMap<u64, Subscription>,
+    on_notification: Rc<dyn Fn(PubSubNotification)>,
+    reconnect_attempts: u32,
+    reconnect: Option<Timeout>,
+    closed: bool,
+    on_open: Option<Closure<dyn FnMut()>>,
+    on_message: Option<Closure<dyn FnMut(MessageEvent)>>,
+    on_close: Option<Closure<dyn FnMut(CloseEvent)>>,
+}
+
+/// Client for the cluster's WebSocket PubSub endpoint. Dropped connections
+/// are reopened with an increasing delay and all subscriptions are sent again.
+#[derive(Clone)]
+pub struct PubSubClient {
+    state: Rc<RefCell<PubSubState>>,
+}
+
+impl PubSubClient {
+    pub fn connect(endpoint: &str, on_notification: impl Fn(PubSubNotification) + 'static) -> Self {
+        let client = Self {
+            state: Rc::new(RefCell::new(PubSubState {
+                endpoint: endpoint.to_string(),
+                socket: None,
+                subscriptions: Vec::default(),
+                pending: HashMap::default(),
+                active: HashMap::default(),
+                on_notification: Rc::new(on_notification),
+                reconnect_attempts: 0,
+                reconnect: None,
+                closed: false,
+                on_open: None,
+                on_message: None,
+                on_close: None,
+            })),
+        };
+
+        client.open_socket();
+
+        client
+    }
+
+    pub fn endpoint(&self) -> String {
+        self.state.borrow().endpoint.clone()
+    }
+
+    pub fn subscribe(&self, subscription: Subscription) {
+        let mut state = self.state.borrow_mut();
+
+        if state.subscriptions.contains(&subscription) {
+            return;
+        }
+
+        state.subscriptions.push(subscription.clone());
+        state.send_subscribe(subscription);
+    }
+
+    pub fn unsubscribe(&self, subscription: &Subscription) {
+        let mut state = self.state.borrow_mut();
+
+        state
+            .subscriptions
+            .retain(|current| current != subscription);
+        state.pending.retain(|_, current| current != subscription);
+
+        let subscription_id = state
+            .active
+            .iter()
+            .find(|(_, current)| *current == subscription)
+            .map(|(subscription_id, _)| *subscription_id);
+
+        if let Some(subscription_id) = subscription_id {
+            state.active.remove(&subscription_id);
+            state.send(RpcRequest::new(
+                subscription.unsubscribe_method(),
+                json!([subscription_id]),
+            ));
+        }
+    }
+
+    /// Closes the socket for good, no reconnect is attempted afterwards
+    pub fn close(&self) {
+        let mut state = self.state.borrow_mut();
+
+        state.closed = true;
+        state.reconnect.take();
+
+        if let Some(socket) = state.socket.take() {
+            socket.set_onopen(None);
+            socket.set_onmessage(None);
+            socket.set_onclose(None);
+            socket.close().ok();
+        }
+    }
+
+    fn open_socket(&self) {
+        let mut state = self.state.borrow_mut();
+
+        let socket = match WebSocket::new(&state.endpoint) {
+            Ok(socket) => socket,
+            Err(_) => {
+                drop(state);
+                self.schedule_reconnect();
+                return;
+            }
+        };
+
+        // The handlers only hold a weak reference so dropping
+        // every `PubSubClient` frees the socket and its state
+        let weak = Rc::downgrade(&self.state);
+        let on_open = Closure::<dyn FnMut()>::new(move || {
+            if let Some(client) = Self::upgrade(&weak) {
+                client.handle_open();
+            }
+        });
+
+        let weak = Rc::downgrade(&self.state);
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            if let (Some(client), Some(message)) = (Self::upgrade(&weak), event.data().as_string())
+            {
+                client.handle_message(&message);
+            }
+        });
+
+        let weak = Rc::downgrade(&self.state);
+        let on_close = Closure::<dyn FnMut(CloseEvent)>::new(move |_event: CloseEvent| {
+            if let Some(client) = Self::upgrade(&weak) {
+                client.handle_close();
+            }
+        });
+
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+        state.socket.replace(socket);
+        state.on_open.replace(on_open);
+        state.on_message.replace(on_message);
+        state.on_close.replace(on_close);
+    }
+
+    fn upgrade(weak: &Weak<RefCell<PubSubState>>) -> Option<Self> {
+        weak.upgrade().map(|state| Self { state })
+    }
+
+    fn handle_open(&self) {
+        let mut state = self.state.borrow_mut();
+
+        state.reconnect_attempts = 0;
+        state.pending.clear();
+        state.active.clear();
+
+        state
+            .subscriptions
+            .clone()
+            .into_iter()
+            .for_each(|subscription| state.send_subscribe(subscription));
+    }
+
+    fn handle_message(&self, message: &str) {
+        let Ok(message) = serde_json::from_str::<Value>(message) else {
+            return;
+        };
+
+        // Responses to subscribe requests carry the subscription id as `result`
+        if let (Some(request_id), Some(subscription_id)) = (
+            message.get("id").and_then(Value::as_u64),
+            message.get("result").and_then(Value::as_u64),
+        ) {
+            let mut state = self.state.borrow_mut();
+            if let Some(subscription) = state.pending.remove(&request_id) {
+                state.active.insert(subscription_id, subscription);
+            }
+
+            return;
+        }
+
+        let Ok(notification) = serde_json::from_value::<NotificationMessage>(message) else {
+            return;
+        };
+
+        let mut state = self.state.borrow_mut();
+        let Some(subscription) = state.active.get(&notification.params.subscription).cloned()
+        else {
+            return;
+        };
+
+        // The node drops a signature subscription after its first notification
+        if let Subscription::Signature(_) = subscription {
+            state.active.remove(&notification.params.subscription);
+            state
+                .subscriptions
+                .retain(|current| current != &subscription);
+        }
+
+        let on_notification = state.on_notification.clone();
+        drop(state);
+
+        if let Some(notification) =
+            PubSubNotification::parse(&subscription, notification.params.result)
+        {
+            on_notification(notification);
+        }
+    }
+
+    fn handle_close(&self) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.socket.take();
+            state.pending.clear();
+            state.active.clear();
+
+            if state.closed {
+                return;
+            }
+        }
+
+        self.schedule_reconnect();
+    }
+
+    fn schedule_reconnect(&self) {
+        let mut state = self.state.borrow_mut();
+
+        let delay = RECONNECT_DELAY_MS
+            .saturating_mul(2u32.saturating_pow(state.reconnect_attempts))
+            .min(MAX_RECONNECT_DELAY_MS);
+        state.reconnect_attempts += 1;
+
+        let weak = Rc::downgrade(&self.state);
+        state.reconnect.replace(Timeout::new(delay, move || {
+            if let Some(client) = Self::upgrade(&weak) {
+                if !client.state.borrow().closed {
+                    client.open_socket();
+                }
+            }
+        }));
+    }
+}
+
+impl PubSubState {
+    fn send_subscribe(&mut self, subscription: Subscription) {
+        let request = subscription.subscribe_request();
+
+        if self.send(request.clone()) {
+            self.pending.insert(request.id(), subscription);
+        }
+    }
+
+    /// Returns `false` if the socket is not open yet, the request is
+    /// then sent by the `open` handler together with the other subscriptions
+    fn send(&self, request: RpcRequest) -> bool {
+        let Some(socket) = self
+            .socket
+            .as_ref()
+            .filter(|socket| socket.ready_state() == WebSocket::OPEN)
+        else {
+            return false;
+        };
+
+        request
+            .to_json()
+            .ok()
+            .map(|body| socket.send_with_str(&body).is_ok())
+            .unwrap_or_default()
+    }
+}
+
+impl core::fmt::Debug for PubSubClient {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let state = self.state.borrow();
+
+        f.debug_struct("PubSubClient")
+            .field("endpoint", &state.endpoint)
+            .field("subscriptions", &state.subscriptions)
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
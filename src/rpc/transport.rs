@@ -0,0 +1,50 @@
+use crate::RpcResult;
+
+/// Sends a serialized JSON-RPC body to a node and returns the response body.
+/// The RPC layer only reaches the network through this trait so the same
+/// code runs in the browser, in native tools and in unit tests.
+pub trait RpcTransport {
+    async fn send(&self, body: &str) -> RpcResult<String>;
+}
+
+/// Sends requests with a blocking HTTP client. Blocking the executor
+/// is fine for the command line tools and tests this is meant for.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NativeTransport {
+    endpoint: String,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl NativeTransport {
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+        }
+    }
+
+    pub fn endpoint(&self) -> &str {
+        self.endpoint.as_str()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RpcTransport for NativeTransport {
+    async fn send(&self, body: &str) -> RpcResult<String> {
+        let response = ureq::post(&self.endpoint)
+            .set("content-type", "application/json")
+            .set("Accept", "application/json")
+            .send_string(body);
+
+        // Like the browser `fetch` api, error statuses still return their
+        // body since nodes put JSON-RPC error objects in it
+        let response = match response {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(error) => return Err(crate::RpcError::Transport(error.to_string())),
+        };
+
+        response
+            .into_string()
+            .map_err(|error| crate::RpcError::Transport(error.to_string()))
+    }
+}
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+
+/// The level of commitment a node uses when answering a query,
+/// ordered from the least to the most final
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    #[default]
+    Finalized,
+}
+
+/// The encoding a node uses for account and transaction data
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Encoding {
+    Base58,
+    Base64,
+    #[serde(rename = "base64+zstd")]
+    Base64Zstd,
+    Json,
+    JsonParsed,
+}
+
+/// Config accepted by methods that only take a commitment,
+/// like `getBalance` and `getLatestBlockhash`
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitmentConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<Commitment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<u64>,
+}
+
+impl CommitmentConfig {
+    pub fn new(commitment: Commitment) -> Self {
+        Self {
+            commitment: Some(commitment),
+            ..Default::default()
+        }
+    }
+}
+
+/// Limits the returned account data to `length` bytes starting at `offset`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub struct DataSlice {
+    pub offset: usize,
+    pub length: usize,
+}
+
+/// Config accepted by methods that return account data,
+/// like `getAccountInfo` and `getTokenAccountsByOwner`
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfoConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<Commitment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<Encoding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_slice: Option<DataSlice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<u64>,
+}
+
+impl AccountInfoConfig {
+    pub fn new(encoding: Encoding) -> Self {
+        Self {
+            encoding: Some(encoding),
+            ..Default::default()
+        }
+    }
+}
+
+/// Selects which token accounts `getTokenAccountsByOwner` returns
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenAccountsFilter {
+    Mint(String),
+    ProgramId(String),
+}
+
+/// Config for `getSignaturesForAddress`
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignaturesForAddressConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<Commitment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<u64>,
+}
+
+/// Config for `getTransaction`
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<Commitment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<Encoding>,
+    /// Without it the node refuses versioned transactions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_supported_transaction_version: Option<u8>,
+}
+
+/// Config for `simulateTransaction`
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateTransactionConfig {
+    /// Cannot be set along with `replace_recent_blockhash`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sig_verify: Option<bool>,
+    /// Simulates with the latest blockhash instead of the one in the message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replace_recent_blockhash: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<Commitment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<Encoding>,
+    /// The accounts to return as they are after the simulation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accounts: Option<SimulateAccountsConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateAccountsConfig {
+    pub addresses: Vec<String>,
+    pub encoding: Encoding,
+}
+
+/// Config for `sendTransaction`
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendTransactionConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_preflight: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preflight_commitment: Option<Commitment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<Encoding>,
+    /// How often the node itself retries, `0` leaves rebroadcasting to the caller
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<u64>,
+}
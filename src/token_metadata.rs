@@ -0,0 +1,415 @@
+use std::{collections::HashMap, str::FromStr};
+
+use dioxus::prelude::*;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    AccountInfoConfig, EncodedAccount, Encoding, FetchReq, RpcClient, RpcResult, RpcTransport,
+    TokenAccountResponse, TokenExtension, CLUSTER_STORAGE, MAX_ACCOUNTS_PER_CALL, TOKEN_METADATA,
+};
+
+pub const METADATA_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+
+/// Off-chain JSON that does not answer within this time is skipped
+const OFF_CHAIN_TIMEOUT_MS: u32 = 5_000;
+
+/// Name, symbol and logo of a mint
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    /// Read from the off-chain JSON at `uri`
+    pub image: Option<String>,
+}
+
+impl TokenMetadata {
+    /// The Metaplex metadata account of `mint`
+    pub fn metaplex_address(mint: &str) -> Option<Pubkey> {
+        let program_id = Pubkey::from_str(METADATA_PROGRAM_ID).ok()?;
+        let mint = Pubkey::from_str(mint).ok()?;
+
+        let (address, _bump) = Pubkey::find_program_address(
+            &[b"metadata", program_id.as_ref(), mint.as_ref()],
+            &program_id,
+        );
+
+        Some(address)
+    }
+
+    /// Decodes a Borsh encoded Metaplex `Metadata` account up to its `uri`
+    pub fn from_metaplex(data: &[u8]) -> Option<Self> {
+        // key (1 byte), update authority (32 bytes) and mint (32 bytes)
+        let mut offset = 65;
+
+        Some(Self {
+            name: read_string(data, &mut offset)?,
+            symbol: read_string(data, &mut offset)?,
+            uri: read_string(data, &mut offset)?,
+            image: None,
+        })
+    }
+
+    /// Token-2022 mints can store their metadata in the mint itself
+    pub fn from_extensions(extensions: &[TokenExtension]) -> Option<Self> {
+        extensions.iter().find_map(|extension| match extension {
+            TokenExtension::TokenMetadata { name, symbol, uri } => Some(Self {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                uri: uri.clone(),
+                image: None,
+            }),
+            _ => None,
+        })
+    }
+}
+
+/// Reads a Borsh `String`, a little endian `u32` length followed by
+/// the bytes. Metaplex pads the strings with `\0` to a fixed length.
+fn read_string(data: &[u8], offset: &mut usize) -> Option<String> {
+    let len_end = offset.checked_add(4)?;
+    let len_bytes = data.get(*offset..len_end)?;
+    let len = u32::from_le_bytes(len_bytes.try_into().ok()?) as usize;
+    *offset = len_end;
+
+    // A corrupt length can overflow `usize` on wasm32
+    let end = offset.checked_add(len)?;
+    let bytes = data.get(*offset..end)?;
+    *offset = end;
+
+    Some(
+        String::from_utf8_lossy(bytes)
+            .trim_end_matches('\0')
+            .trim()
+            .to_string(),
+    )
+}
+
+/// Metadata of every mint looked up so far, per cluster name since the
+/// same mint address holds other metadata, or none, on another cluster.
+/// `None` marks mints without metadata and lookups that are still running.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub(crate) struct TokenMetadataCache {
+    clusters: HashMap<String, HashMap<String, Option<TokenMetadata>>>,
+}
+
+impl TokenMetadataCache {
+    pub fn get(&self, cluster_name: &str, mint: &str) -> Option<&TokenMetadata> {
+        self.clusters
+            .get(cluster_name)
+            .and_then(|mints| mints.get(mint))
+            .and_then(Option::as_ref)
+    }
+
+    pub fn contains(&self, cluster_name: &str, mint: &str) -> bool {
+        self.clusters
+            .get(cluster_name)
+            .is_some_and(|mints| mints.contains_key(mint))
+    }
+
+    pub fn insert(
+        &mut self,
+        cluster_name: &str,
+        mint: &str,
+        metadata: Option<TokenMetadata>,
+    ) -> &mut Self {
+        self.clusters
+            .entry(cluster_name.to_string())
+            .or_default()
+            .insert(mint.to_string(), metadata);
+
+        self
+    }
+
+    pub fn remove(&mut self, cluster_name: &str, mint: &str) -> &mut Self {
+        if let Some(mints) = self.clusters.get_mut(cluster_name) {
+            mints.remove(mint);
+        }
+
+        self
+    }
+
+    pub fn set_image(&mut self, cluster_name: &str, mint: &str, image: String) -> &mut Self {
+        if let Some(Some(metadata)) = self
+            .clusters
+            .get_mut(cluster_name)
+            .and_then(|mints| mints.get_mut(mint))
+        {
+            metadata.image.replace(image);
+        }
+
+        self
+    }
+}
+
+/// The metadata of `mint` on the active cluster, if it was resolved
+pub fn token_metadata(mint: &str) -> Option<TokenMetadata> {
+    let cluster_name = CLUSTER_STORAGE.read().active_cluster().name().to_string();
+
+    TOKEN_METADATA.read().get(&cluster_name, mint).cloned()
+}
+
+/// The symbol of `mint` on the active cluster, the shortened mint address
+/// for tokens without metadata
+pub fn token_symbol(mint: &str) -> String {
+    token_metadata(mint)
+        .map(|metadata| metadata.symbol)
+        .filter(|symbol| !symbol.is_empty())
+        .unwrap_or_else(|| {
+            wallet_adapter::Utils::shorten_base58(mint)
+                .map(|mint| mint.to_string())
+                .unwrap_or(mint.to_string())
+        })
+}
+
+/// Loads the Metaplex metadata of `mints`, `None` for mints without metadata
+pub async fn load_token_metadata<T: RpcTransport>(
+    client: &RpcClient<T>,
+    mints: &[String],
+) -> RpcResult<Vec<(String, Option<TokenMetadata>)>> {
+    let addresses = mints
+        .iter()
+        .filter_map(|mint| {
+            TokenMetadata::metaplex_address(mint).map(|address| (mint.clone(), address.to_string()))
+        })
+        .collect::<Vec<(String, String)>>();
+
+    let mut resolved = Vec::<(String, Option<TokenMetadata>)>::new();
+
+    for addresses in addresses.chunks(MAX_ACCOUNTS_PER_CALL) {
+        let pubkeys = addresses
+            .iter()
+            .map(|(_, address)| address.clone())
+            .collect::<Vec<String>>();
+
+        let accounts = client
+            .get_multiple_accounts::<EncodedAccount>(
+                &pubkeys,
+                AccountInfoConfig::new(Encoding::Base64),
+            )
+            .await?;
+
+        addresses
+            .iter()
+            .zip(accounts)
+            .for_each(|((mint, _), account)| {
+                let metadata = account
+                    .filter(|account| account.owner == METADATA_PROGRAM_ID)
+                    .and_then(|account| account.decoded_data())
+                    .and_then(|data| TokenMetadata::from_metaplex(&data));
+
+                resolved.push((mint.clone(), metadata));
+            });
+    }
+
+    Ok(resolved)
+}
+
+/// Resolves the metadata of the token accounts missing from [TOKEN_METADATA],
+/// then loads their logos from the off-chain JSON
+pub async fn resolve_token_metadata(token_accounts: Vec<TokenAccountResponse>) {
+    // Results of a lookup that outlives a cluster switch stay with their cluster
+    let cluster_name = CLUSTER_STORAGE.peek().active_cluster().name().to_string();
+    let mut resolved = Vec::<(String, TokenMetadata)>::new();
+    let mut mints = Vec::<String>::new();
+
+    for token_account in token_accounts.iter() {
+        let mint = token_account.mint();
+        if TOKEN_METADATA.peek().contains(&cluster_name, &mint) || mints.contains(&mint) {
+            continue;
+        }
+
+        match TokenMetadata::from_extensions(&token_account.extensions()) {
+            Some(metadata) => {
+                TOKEN_METADATA
+                    .write()
+                    .insert(&cluster_name, &mint, Some(metadata.clone()));
+                resolved.push((mint, metadata));
+            }
+            None => mints.push(mint),
+        }
+    }
+
+    if !mints.is_empty() {
+        // Marks the mints as looked up so a second resolver skips them
+        mints.iter().for_each(|mint| {
+            TOKEN_METADATA.write().insert(&cluster_name, mint, None);
+        });
+
+        match load_token_metadata(&RpcClient::new(), &mints).await {
+            Ok(metadata) => metadata.into_iter().for_each(|(mint, metadata)| {
+                if let Some(metadata) = metadata.as_ref() {
+                    resolved.push((mint.clone(), metadata.clone()));
+                }
+                TOKEN_METADATA
+                    .write()
+                    .insert(&cluster_name, &mint, metadata);
+            }),
+            // Tried again the next time the accounts load
+            Err(_) => mints.iter().for_each(|mint| {
+                TOKEN_METADATA.write().remove(&cluster_name, mint);
+            }),
+        }
+    }
+
+    for (mint, metadata) in resolved {
+        if metadata.uri.is_empty() {
+            continue;
+        }
+
+        if let Some(image) = load_image(&metadata.uri).await {
+            TOKEN_METADATA
+                .write()
+                .set_image(&cluster_name, &mint, image);
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OffChainMetadata {
+    image: Option<String>,
+}
+
+/// Reads `image` from the off-chain JSON, `None` if the host
+/// is unreachable or does not allow cross-origin requests
+async fn load_image(uri: &str) -> Option<String> {
+    let body = FetchReq::new("GET")
+        .and_then(|request| request.add_header("Accept", "application/json"))
+        .ok()?
+        .set_timeout(OFF_CHAIN_TIMEOUT_MS)
+        .send_to(uri)
+        .await
+        .ok()?;
+
+    serde_json::from_str::<OffChainMetadata>(&body)
+        .ok()?
+        .image
+        .filter(|image| image.starts_with("https://") || image.starts_with("http://"))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{block_on, encoded_account, MockTransport, RpcFixture};
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const JITOSOL: &str = "J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn";
+
+    /// A Metaplex `Metadata` account with the padding the program writes
+    fn metaplex_account(name: &str, symbol: &str, uri: &str) -> Vec<u8> {
+        let mut data = vec![4u8];
+        data.extend([1u8; 32]);
+        data.extend([2u8; 32]);
+
+        for (value, len) in [(name, 32), (symbol, 10), (uri, 200)] {
+            let mut bytes = value.as_bytes().to_vec();
+            bytes.resize(len, 0);
+
+            data.extend((len as u32).to_le_bytes());
+            data.extend(bytes);
+        }
+        // seller fee basis points and the fields after it are not read
+        data.extend([0u8; 16]);
+
+        data
+    }
+
+    #[test]
+    fn decodes_metaplex_metadata() {
+        let data = metaplex_account("USD Coin", "USDC", "https://example.com/usdc.json");
+
+        assert_eq!(
+            TokenMetadata::from_metaplex(&data),
+            Some(TokenMetadata {
+                name: "USD Coin".to_string(),
+                symbol: "USDC".to_string(),
+                uri: "https://example.com/usdc.json".to_string(),
+                image: None,
+            })
+        );
+
+        assert_eq!(TokenMetadata::from_metaplex(&data[..100]), None);
+        assert_eq!(read_string(&data, &mut (usize::MAX - 1)), None);
+    }
+
+    #[test]
+    fn caches_metadata_per_cluster() {
+        let mint = USDC;
+        let metadata = TokenMetadata {
+            symbol: "USDC".to_string(),
+            ..Default::default()
+        };
+
+        let mut cache = TokenMetadataCache::default();
+        cache
+            .insert("devnet", mint, None)
+            .insert("mainnet", mint, Some(metadata.clone()))
+            .set_image("mainnet", mint, "https://example.com/usdc.png".to_string());
+
+        // A mint without metadata on one cluster is still looked up on the others
+        assert!(cache.contains("devnet", mint));
+        assert!(!cache.contains("testnet", mint));
+        assert_eq!(cache.get("devnet", mint), None);
+        assert_eq!(
+            cache
+                .get("mainnet", mint)
+                .and_then(|metadata| metadata.image.as_deref()),
+            Some("https://example.com/usdc.png")
+        );
+
+        cache.remove("mainnet", mint);
+        assert!(!cache.contains("mainnet", mint));
+        assert!(cache.contains("devnet", mint));
+    }
+
+    #[test]
+    fn derives_the_metadata_address() {
+        let address = TokenMetadata::metaplex_address(USDC).unwrap();
+
+        assert_eq!(
+            address.to_string(),
+            "5x38Kp4hvdomTCnCrAny4UtMUt5rQBdB6px2K1Ui45Wq"
+        );
+        assert_eq!(TokenMetadata::metaplex_address("not a mint"), None);
+    }
+
+    #[test]
+    fn loads_metadata_of_several_mints() {
+        let data = metaplex_account("USD Coin", "USDC", "https://example.com/usdc.json");
+        let addresses =
+            [USDC, JITOSOL].map(|mint| TokenMetadata::metaplex_address(mint).unwrap().to_string());
+
+        let client = RpcClient::with_transport(MockTransport::replay(vec![RpcFixture {
+            method: "getMultipleAccounts".to_string(),
+            params: json!([addresses, { "encoding": "base64" }]),
+            response: json!({
+                "jsonrpc": "2.0",
+                "result": {
+                    "context": { "slot": 341197053 },
+                    "value": [
+                        encoded_account(METADATA_PROGRAM_ID, 5616720, &data),
+                        null
+                    ]
+                }
+            }),
+        }]));
+
+        let metadata = block_on(load_token_metadata(
+            &client,
+            &[
+                USDC.to_string(),
+                JITOSOL.to_string(),
+                "not a mint".to_string(),
+            ],
+        ))
+        .unwrap();
+
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata[0].0, USDC);
+        assert_eq!(metadata[0].1.as_ref().unwrap().symbol, "USDC");
+        assert_eq!(metadata[1], (JITOSOL.to_string(), None));
+    }
+}
@@ -0,0 +1,337 @@
+use std::str::FromStr;
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use wallet_adapter::{WalletError, WalletResult};
+
+use crate::{
+    budgeted_transaction, token_symbol, AccountInfoConfig, DataSlice, EncodedAccount, Encoding,
+    PreparedTransaction, RpcClient, RpcResult, RpcTransport, TokenAmount, TokenProgram,
+    TransactionLifetime, SYSTEM_PROGRAM_ID,
+};
+
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+/// `TransferChecked` in the instruction enum shared by both token programs
+const TRANSFER_CHECKED: u8 = 12;
+
+/// `CreateIdempotent` of the associated token account program
+const CREATE_IDEMPOTENT: u8 = 1;
+
+/// A transfer of `amount` from the token account `source` to the associated
+/// token account of `recipient`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TokenTransfer {
+    source: Pubkey,
+    mint: Pubkey,
+    recipient: Pubkey,
+    amount: TokenAmount,
+    program: TokenProgram,
+}
+
+impl TokenTransfer {
+    pub fn new(
+        source: &str,
+        mint: &str,
+        recipient: &str,
+        amount: TokenAmount,
+        program: TokenProgram,
+    ) -> WalletResult<Self> {
+        let parse = |address: &str, name: &str| {
+            Pubkey::from_str(address.trim()).or(Err(WalletError::Op(format!("Invalid {name}"))))
+        };
+
+        Ok(Self {
+            source: parse(source, "Token Account Address")?,
+            mint: parse(mint, "Mint Address")?,
+            recipient: parse(recipient, "Recipient Address")?,
+            amount,
+            program,
+        })
+    }
+
+    /// The associated token account of the recipient, the tokens are sent there
+    pub fn recipient_token_account(&self) -> Pubkey {
+        associated_token_address(&self.recipient, &self.mint, self.program)
+    }
+
+    /// `authority` owns the source account and pays for the recipient's
+    /// token account when `create_recipient_account` is set
+    pub fn instructions(
+        &self,
+        authority: Pubkey,
+        create_recipient_account: bool,
+    ) -> Vec<Instruction> {
+        let destination = self.recipient_token_account();
+        let mut instructions = Vec::<Instruction>::new();
+
+        if create_recipient_account {
+            instructions.push(create_associated_token_account_idempotent(
+                &authority,
+                &self.recipient,
+                &self.mint,
+                self.program,
+            ));
+        }
+
+        instructions.push(transfer_checked(
+            self.program,
+            &self.source,
+            &self.mint,
+            &destination,
+            &authority,
+            self.amount,
+        ));
+
+        instructions
+    }
+
+    /// Describes the transfer in the pending transactions list
+    pub fn description(&self) -> String {
+        format!(
+            "Send {} {}",
+            self.amount,
+            token_symbol(&self.mint.to_string())
+        )
+    }
+}
+
+/// Derives the associated token account of `owner` for `mint`. The token
+/// program is part of the seeds so Token-2022 accounts have other addresses.
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, program: TokenProgram) -> Pubkey {
+    let (address, _bump) = Pubkey::find_program_address(
+        &[
+            owner.as_ref(),
+            program_id(program.id()).as_ref(),
+            mint.as_ref(),
+        ],
+        &program_id(ASSOCIATED_TOKEN_PROGRAM_ID),
+    );
+
+    address
+}
+
+/// Creates the associated token account unless it already exists
+pub fn create_associated_token_account_idempotent(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    program: TokenProgram,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id(ASSOCIATED_TOKEN_PROGRAM_ID),
+        &[CREATE_IDEMPOTENT],
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(owner, mint, program), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(program_id(SYSTEM_PROGRAM_ID), false),
+            AccountMeta::new_readonly(program_id(program.id()), false),
+        ],
+    )
+}
+
+/// The program checks `amount` against the decimals of the mint so an
+/// amount read with the wrong decimals fails instead of sending too much
+pub fn transfer_checked(
+    program: TokenProgram,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: TokenAmount,
+) -> Instruction {
+    let mut data = vec![TRANSFER_CHECKED];
+    data.extend(amount.raw().to_le_bytes());
+    data.push(amount.decimals());
+
+    Instruction::new_with_bytes(
+        program_id(program.id()),
+        &data,
+        vec![
+            AccountMeta::new(*source, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// The ids are constants so parsing them cannot fail
+pub(crate) fn program_id(id: &str) -> Pubkey {
+    Pubkey::from_str(id).unwrap_or_default()
+}
+
+/// Whether `address` exists, without downloading its data
+pub async fn account_exists<T: RpcTransport>(
+    client: &RpcClient<T>,
+    address: &Pubkey,
+) -> RpcResult<bool> {
+    let config = AccountInfoConfig {
+        data_slice: Some(DataSlice {
+            offset: 0,
+            length: 0,
+        }),
+        ..AccountInfoConfig::new(Encoding::Base64)
+    };
+
+    client
+        .get_account_info::<EncodedAccount>(&address.to_string(), config)
+        .await
+        .map(|account| account.is_some())
+}
+
+/// Builds `transfer` with the latest blockhash, ready to be reviewed. The
+/// recipient's token account is created first if it does not exist yet.
+pub async fn prepare_token_transfer(
+    transfer: &TokenTransfer,
+    public_key_bytes: [u8; 32],
+) -> WalletResult<PreparedTransaction> {
+    let client = RpcClient::new();
+    let authority = Pubkey::new_from_array(public_key_bytes);
+
+    let create_recipient_account =
+        !account_exists(&client, &transfer.recipient_token_account()).await?;
+
+    let lifetime = TransactionLifetime::resolve(&client, &authority).await?;
+
+    let tx = budgeted_transaction(
+        &client,
+        &transfer.instructions(authority, create_recipient_account),
+        &authority,
+        &[],
+        &lifetime,
+    )
+    .await?;
+
+    Ok(PreparedTransaction::send(
+        tx,
+        lifetime.last_valid_block_height(),
+        &transfer.description(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{block_on, MockTransport, RpcFixture, MINT, OWNER, RECIPIENT, SOURCE};
+
+    fn transfer(program: TokenProgram) -> TokenTransfer {
+        TokenTransfer::new(
+            SOURCE,
+            MINT,
+            RECIPIENT,
+            TokenAmount::new(2_500_000, 6),
+            program,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn builds_a_checked_transfer() {
+        let authority = Pubkey::from_str(OWNER).unwrap();
+        let transfer = transfer(TokenProgram::Token);
+        let instructions = transfer.instructions(authority, false);
+
+        assert_eq!(instructions.len(), 1);
+        assert_eq!(
+            instructions[0].program_id.to_string(),
+            TokenProgram::Token.id()
+        );
+        assert_eq!(
+            instructions[0].data,
+            [12, 0xA0, 0x25, 0x26, 0, 0, 0, 0, 0, 6]
+        );
+
+        let accounts = &instructions[0].accounts;
+        assert_eq!(accounts[0], AccountMeta::new(transfer.source, false));
+        assert_eq!(accounts[1], AccountMeta::new_readonly(transfer.mint, false));
+        assert_eq!(
+            accounts[2],
+            AccountMeta::new(transfer.recipient_token_account(), false)
+        );
+        assert_eq!(accounts[3], AccountMeta::new_readonly(authority, true));
+
+        assert!(TokenTransfer::new(
+            SOURCE,
+            MINT,
+            "not an address",
+            TokenAmount::default(),
+            TokenProgram::Token
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn creates_the_recipient_account_first() {
+        let authority = Pubkey::from_str(OWNER).unwrap();
+
+        for program in [TokenProgram::Token, TokenProgram::Token2022] {
+            let transfer = transfer(program);
+            let instructions = transfer.instructions(authority, true);
+
+            assert_eq!(instructions.len(), 2);
+            assert_eq!(
+                instructions[0].program_id.to_string(),
+                ASSOCIATED_TOKEN_PROGRAM_ID
+            );
+            assert_eq!(instructions[0].data, [1]);
+            assert_eq!(
+                instructions[0].accounts[0],
+                AccountMeta::new(authority, true)
+            );
+            assert_eq!(
+                instructions[0].accounts[1],
+                AccountMeta::new(transfer.recipient_token_account(), false)
+            );
+            assert_eq!(instructions[0].accounts[5].pubkey.to_string(), program.id());
+            assert_eq!(instructions[1].program_id.to_string(), program.id());
+        }
+
+        // The token program is one of the seeds
+        assert_ne!(
+            transfer(TokenProgram::Token).recipient_token_account(),
+            transfer(TokenProgram::Token2022).recipient_token_account()
+        );
+    }
+
+    #[test]
+    fn checks_whether_accounts_exist() {
+        let fixture = |address: &str, value| RpcFixture {
+            method: "getAccountInfo".to_string(),
+            params: json!([
+                address,
+                { "encoding": "base64", "dataSlice": { "offset": 0, "length": 0 } }
+            ]),
+            response: json!({
+                "jsonrpc": "2.0",
+                "result": { "context": { "slot": 1 }, "value": value }
+            }),
+        };
+
+        let client = RpcClient::with_transport(MockTransport::replay(vec![
+            fixture(
+                SOURCE,
+                json!({
+                    "data": ["", "base64"],
+                    "owner": TokenProgram::Token.id(),
+                    "lamports": 2039280,
+                    "executable": false,
+                    "rentEpoch": 0
+                }),
+            ),
+            fixture(RECIPIENT, json!(null)),
+        ]));
+
+        let exists =
+            |address| block_on(account_exists(&client, &Pubkey::from_str(address).unwrap()));
+
+        assert_eq!(exists(SOURCE), Ok(true));
+        assert_eq!(exists(RECIPIENT), Ok(false));
+    }
+}
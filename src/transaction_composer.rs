@@ -0,0 +1,491 @@
+use std::str::FromStr;
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::AddressLookupTableAccount,
+    pubkey::Pubkey,
+    system_instruction::create_account_with_seed,
+};
+use wallet_adapter::{WalletError, WalletResult};
+
+use crate::{
+    budgeted_size, budgeted_transaction, fetch_lookup_table, program_id, transfer_instruction,
+    Lamports, PreparedTransaction, RpcClient, RpcTransport, TokenAmount, TokenProgram,
+    TokenTransfer, TransactionLifetime, COMPUTE_BUDGET_PROGRAM_ID, MEMO_PROGRAM_ID,
+};
+
+/// An account of a raw instruction as typed by the user
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct RawAccountMeta {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// An instruction of the [TransactionComposer], the fields hold what the
+/// user typed and are only parsed when the transaction is built
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ComposedInstruction {
+    /// `amount` is in SOL
+    SolTransfer {
+        recipient: String,
+        amount: String,
+    },
+    /// Sends from the token account `source` of the wallet, the associated
+    /// token account of `recipient` is created if it does not exist
+    TokenTransfer {
+        source: String,
+        mint: String,
+        decimals: u8,
+        program: TokenProgram,
+        recipient: String,
+        amount: String,
+    },
+    Memo {
+        memo: String,
+    },
+    /// Creates the account derived from the wallet with `seed` since the
+    /// wallet cannot sign for a new keypair, `lamports` is in SOL
+    CreateAccount {
+        seed: String,
+        lamports: String,
+        space: String,
+        owner: String,
+    },
+    /// `data` is hex encoded
+    Raw {
+        program_id: String,
+        accounts: Vec<RawAccountMeta>,
+        data: String,
+    },
+}
+
+impl ComposedInstruction {
+    /// An empty instruction of each kind, in the order they are offered
+    pub const KINDS: [ComposedInstruction; 5] = [
+        Self::SolTransfer {
+            recipient: String::new(),
+            amount: String::new(),
+        },
+        Self::TokenTransfer {
+            source: String::new(),
+            mint: String::new(),
+            decimals: 0,
+            program: TokenProgram::Token,
+            recipient: String::new(),
+            amount: String::new(),
+        },
+        Self::Memo {
+            memo: String::new(),
+        },
+        Self::CreateAccount {
+            seed: String::new(),
+            lamports: String::new(),
+            space: String::new(),
+            owner: String::new(),
+        },
+        Self::Raw {
+            program_id: String::new(),
+            accounts: Vec::new(),
+            data: String::new(),
+        },
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::SolTransfer { .. } => "SOL Transfer",
+            Self::TokenTransfer { .. } => "Token Transfer",
+            Self::Memo { .. } => "Memo",
+            Self::CreateAccount { .. } => "Create Account",
+            Self::Raw { .. } => "Raw Instruction",
+        }
+    }
+
+    /// Parses the fields into the instructions signed by `payer`, a token
+    /// transfer takes two when the recipient's token account is created
+    pub fn instructions(&self, payer: &Pubkey) -> WalletResult<Vec<Instruction>> {
+        match self {
+            Self::SolTransfer { recipient, amount } => Ok(vec![transfer_instruction(
+                *payer,
+                recipient.trim(),
+                Lamports::from_str(amount)?,
+            )?]),
+            Self::TokenTransfer {
+                source,
+                mint,
+                decimals,
+                program,
+                recipient,
+                amount,
+            } => {
+                let transfer = TokenTransfer::new(
+                    source,
+                    mint,
+                    recipient,
+                    TokenAmount::parse(amount, *decimals)?,
+                    *program,
+                )?;
+
+                Ok(transfer.instructions(*payer, true))
+            }
+            Self::Memo { memo } => {
+                if memo.is_empty() {
+                    return Err(WalletError::Op("The memo is empty".to_string()));
+                }
+
+                Ok(vec![memo_instruction(memo, payer)])
+            }
+            Self::CreateAccount {
+                seed,
+                lamports,
+                space,
+                owner,
+            } => {
+                let space = space
+                    .trim()
+                    .parse::<u64>()
+                    .or(Err(WalletError::Op("Invalid Space".to_string())))?;
+                let owner = parse_pubkey(owner, "Owner Program")?;
+
+                Ok(vec![create_account_with_seed(
+                    payer,
+                    &seeded_account_address(payer, seed, &owner)?,
+                    payer,
+                    seed,
+                    Lamports::from_str(lamports)?.get(),
+                    space,
+                    &owner,
+                )])
+            }
+            Self::Raw {
+                program_id,
+                accounts,
+                data,
+            } => {
+                let program_id = parse_pubkey(program_id, "Program Id")?;
+
+                // The compute budget is set from the priority fee, a second
+                // one fails the transaction with `DuplicateInstruction`
+                if program_id.to_string() == COMPUTE_BUDGET_PROGRAM_ID {
+                    return Err(WalletError::Op(
+                        "The priority fee sets the compute budget, remove this instruction"
+                            .to_string(),
+                    ));
+                }
+
+                let accounts = accounts
+                    .iter()
+                    .map(|account| {
+                        let pubkey = parse_pubkey(&account.pubkey, "Account Address")?;
+
+                        // The wallet is the only signer of the composed transaction
+                        if account.is_signer && pubkey != *payer {
+                            return Err(WalletError::Op(format!(
+                                "Only the connected wallet can sign, not {pubkey}"
+                            )));
+                        }
+
+                        Ok(AccountMeta {
+                            pubkey,
+                            is_signer: account.is_signer,
+                            is_writable: account.is_writable,
+                        })
+                    })
+                    .collect::<WalletResult<Vec<AccountMeta>>>()?;
+
+                Ok(vec![Instruction::new_with_bytes(
+                    program_id,
+                    &parse_hex(data)?,
+                    accounts,
+                )])
+            }
+        }
+    }
+}
+
+/// The instructions of a transaction in the order they run
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct TransactionComposer {
+    pub instructions: Vec<ComposedInstruction>,
+    /// The address lookup table the accounts are compiled against, as typed
+    /// by the user. Empty when the transaction does not use one.
+    pub lookup_table: String,
+}
+
+impl TransactionComposer {
+    pub fn add(&mut self, instruction: ComposedInstruction) -> &mut Self {
+        self.instructions.push(instruction);
+
+        self
+    }
+
+    pub fn remove(&mut self, index: usize) -> &mut Self {
+        if index < self.instructions.len() {
+            self.instructions.remove(index);
+        }
+
+        self
+    }
+
+    /// Swaps the instruction at `index` with the one before it
+    pub fn move_up(&mut self, index: usize) -> &mut Self {
+        if index > 0 && index < self.instructions.len() {
+            self.instructions.swap(index - 1, index);
+        }
+
+        self
+    }
+
+    /// Swaps the instruction at `index` with the one after it
+    pub fn move_down(&mut self, index: usize) -> &mut Self {
+        self.move_up(index + 1)
+    }
+
+    /// Errors name the position of the instruction that failed to parse
+    pub fn instructions(&self, payer: &Pubkey) -> WalletResult<Vec<Instruction>> {
+        if self.instructions.is_empty() {
+            return Err(WalletError::Op("Add an instruction first".to_string()));
+        }
+
+        self.instructions
+            .iter()
+            .enumerate()
+            .map(|(index, instruction)| {
+                instruction
+                    .instructions(payer)
+                    .map_err(|error| match error {
+                        WalletError::Op(message) => {
+                            WalletError::Op(format!("Instruction {}: {message}", index + 1))
+                        }
+                        error => error,
+                    })
+            })
+            .collect::<WalletResult<Vec<Vec<Instruction>>>>()
+            .map(|instructions| instructions.concat())
+    }
+
+    /// The size of the signed transaction compiled against `lookup_tables`,
+    /// including its compute budget and the advance instruction of
+    /// `nonce_account` when it is durable
+    pub fn serialized_size(
+        &self,
+        payer: &Pubkey,
+        lookup_tables: &[AddressLookupTableAccount],
+        nonce_account: Option<&Pubkey>,
+    ) -> WalletResult<usize> {
+        budgeted_size(
+            &self.instructions(payer)?,
+            payer,
+            lookup_tables,
+            nonce_account,
+        )
+        .ok_or(WalletError::Op(
+            "The instructions do not compile into a transaction".to_string(),
+        ))
+    }
+
+    /// Fetches the table of [Self::lookup_table] with `getAccountInfo`
+    pub async fn lookup_tables<T: RpcTransport>(
+        &self,
+        client: &RpcClient<T>,
+    ) -> WalletResult<Vec<AddressLookupTableAccount>> {
+        if self.lookup_table.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let address = parse_pubkey(&self.lookup_table, "Address Lookup Table")?;
+
+        Ok(vec![fetch_lookup_table(client, &address).await?])
+    }
+
+    /// Describes the transaction in the review
+    pub fn description(&self) -> String {
+        self.instructions
+            .iter()
+            .map(ComposedInstruction::label)
+            .collect::<Vec<&str>>()
+            .join(", ")
+    }
+}
+
+/// Writes `memo` to the transaction logs, `signer` has to sign it
+pub fn memo_instruction(memo: &str, signer: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id(MEMO_PROGRAM_ID),
+        memo.as_bytes(),
+        vec![AccountMeta::new_readonly(*signer, true)],
+    )
+}
+
+/// The address [ComposedInstruction::CreateAccount] creates
+pub fn seeded_account_address(base: &Pubkey, seed: &str, owner: &Pubkey) -> WalletResult<Pubkey> {
+    Pubkey::create_with_seed(base, seed, owner).map_err(|error| WalletError::Op(error.to_string()))
+}
+
+/// Decodes hex like `0x0a ff`, whitespace and the `0x` prefix are ignored
+pub fn parse_hex(data: &str) -> WalletResult<Vec<u8>> {
+    let digits = data
+        .trim()
+        .trim_start_matches("0x")
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .collect::<Vec<char>>();
+
+    if digits.len() % 2 != 0 {
+        return Err(WalletError::Op(
+            "The data has an odd number of hex digits".to_string(),
+        ));
+    }
+
+    digits
+        .chunks_exact(2)
+        .map(|pair| {
+            u8::from_str_radix(&pair.iter().collect::<String>(), 16)
+                .or(Err(WalletError::Op(format!("`{data}` is not valid hex"))))
+        })
+        .collect()
+}
+
+/// Builds the composed transaction, ready to be reviewed. It is only
+/// signed unless `send` is set.
+pub async fn prepare_composed_transaction(
+    composer: &TransactionComposer,
+    send: bool,
+    public_key_bytes: [u8; 32],
+) -> WalletResult<PreparedTransaction> {
+    let client = RpcClient::new();
+    let payer = Pubkey::new_from_array(public_key_bytes);
+    let instructions = composer.instructions(&payer)?;
+    let lookup_tables = composer.lookup_tables(&client).await?;
+
+    let lifetime = TransactionLifetime::resolve(&client, &payer).await?;
+    let tx =
+        budgeted_transaction(&client, &instructions, &payer, &lookup_tables, &lifetime).await?;
+
+    let description = composer.description();
+
+    if send {
+        Ok(PreparedTransaction::send(
+            tx,
+            lifetime.last_valid_block_height(),
+            &description,
+        ))
+    } else {
+        Ok(PreparedTransaction::sign(tx, &description))
+    }
+}
+
+fn parse_pubkey(address: &str, name: &str) -> WalletResult<Pubkey> {
+    Pubkey::from_str(address.trim()).or(Err(WalletError::Op(format!("Invalid {name}"))))
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::packet::PACKET_DATA_SIZE;
+
+    use super::*;
+    use crate::SYSTEM_PROGRAM_ID;
+
+    #[test]
+    fn composes_instructions_in_order() {
+        let payer = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+
+        let mut composer = TransactionComposer::default();
+        composer
+            .add(ComposedInstruction::Memo {
+                memo: "Rent for March".to_string(),
+            })
+            .add(ComposedInstruction::SolTransfer {
+                recipient: recipient.to_string(),
+                amount: "1.5".to_string(),
+            })
+            .move_up(1);
+
+        let instructions = composer.instructions(&payer).unwrap();
+        assert_eq!(instructions[0].program_id, program_id(SYSTEM_PROGRAM_ID));
+        assert_eq!(instructions[1], memo_instruction("Rent for March", &payer));
+        assert_eq!(composer.description(), "SOL Transfer, Memo");
+
+        let size = composer.serialized_size(&payer, &[], None).unwrap();
+        let nonce_account = Pubkey::new_unique();
+        assert!(
+            size < composer
+                .serialized_size(&payer, &[], Some(&nonce_account))
+                .unwrap()
+        );
+        assert!(size <= PACKET_DATA_SIZE);
+
+        // The recipients are referenced by their index in the table
+        let recipients = [recipient, Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut transfers = TransactionComposer::default();
+        recipients.iter().for_each(|recipient| {
+            transfers.add(ComposedInstruction::SolTransfer {
+                recipient: recipient.to_string(),
+                amount: "0.1".to_string(),
+            });
+        });
+        let lookup_table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: recipients.to_vec(),
+        };
+        assert!(
+            transfers
+                .serialized_size(&payer, &[lookup_table], None)
+                .unwrap()
+                < transfers.serialized_size(&payer, &[], None).unwrap()
+        );
+
+        composer.remove(0).move_down(0);
+        assert_eq!(composer.instructions.len(), 1);
+        assert_eq!(composer.instructions[0].label(), "Memo");
+    }
+
+    #[test]
+    fn reports_the_instruction_that_failed() {
+        let payer = Pubkey::new_unique();
+
+        let mut composer = TransactionComposer::default();
+        assert!(composer.instructions(&payer).is_err());
+
+        composer
+            .add(ComposedInstruction::Raw {
+                program_id: SYSTEM_PROGRAM_ID.to_string(),
+                accounts: vec![RawAccountMeta {
+                    pubkey: payer.to_string(),
+                    is_signer: true,
+                    is_writable: true,
+                }],
+                data: "0x02000000 e803000000000000".to_string(),
+            })
+            .add(ComposedInstruction::Raw {
+                program_id: SYSTEM_PROGRAM_ID.to_string(),
+                accounts: vec![RawAccountMeta {
+                    pubkey: Pubkey::new_unique().to_string(),
+                    is_signer: true,
+                    is_writable: false,
+                }],
+                data: String::new(),
+            });
+
+        match composer.instructions(&payer) {
+            Err(WalletError::Op(message)) => assert!(message.starts_with("Instruction 2:")),
+            outcome => panic!("Expected the second instruction to fail, got {outcome:?}"),
+        }
+
+        // The priority fee sets the compute budget of every transaction
+        let compute_budget = ComposedInstruction::Raw {
+            program_id: COMPUTE_BUDGET_PROGRAM_ID.to_string(),
+            accounts: Vec::new(),
+            data: "0x02 40420f00".to_string(),
+        };
+        assert!(compute_budget.instructions(&payer).is_err());
+
+        assert_eq!(
+            parse_hex("0x02000000 e803000000000000").unwrap(),
+            [2, 0, 0, 0, 0xe8, 3, 0, 0, 0, 0, 0, 0]
+        );
+        assert!(parse_hex("abc").is_err());
+        assert!(parse_hex("zz").is_err());
+    }
+}
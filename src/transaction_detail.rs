@@ -0,0 +1,591 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+use solana_sdk::bs58;
+use solana_transaction_error::TransactionError;
+
+use crate::{
+    format_decimal, format_sol, Commitment, RpcClient, RpcResult, RpcTransport, TransactionConfig,
+    UiTokenAmount, SOL_DECIMALS, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+
+pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+pub const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+pub const MEMO_V1_PROGRAM_ID: &str = "Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo";
+pub const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+/// Loads a transaction of the active cluster, `None` if the node does not know it
+pub async fn load_transaction<T: RpcTransport>(
+    client: &RpcClient<T>,
+    signature: &str,
+) -> RpcResult<Option<TransactionResponse>> {
+    // Finalizing takes a while, confirmed shows transactions sent moments ago
+    let config = TransactionConfig {
+        commitment: Some(Commitment::Confirmed),
+        ..Default::default()
+    };
+
+    client.get_transaction(signature, config).await
+}
+
+/// A `jsonParsed` transaction as returned by `getTransaction`
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionResponse {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// Left out by nodes for legacy transactions when no max version is requested
+    #[serde(default)]
+    pub version: Option<TransactionVersion>,
+    pub meta: Option<TransactionMeta>,
+    pub transaction: ParsedTransaction,
+}
+
+impl TransactionResponse {
+    pub fn signature(&self) -> &str {
+        self.transaction
+            .signatures
+            .first()
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    pub fn error(&self) -> Option<&TransactionError> {
+        self.meta.as_ref().and_then(|meta| meta.err.as_ref())
+    }
+
+    pub fn fee(&self) -> Option<u64> {
+        self.meta.as_ref().map(|meta| meta.fee)
+    }
+
+    pub fn log_messages(&self) -> &[String] {
+        self.meta
+            .as_ref()
+            .and_then(|meta| meta.log_messages.as_deref())
+            .unwrap_or_default()
+    }
+
+    fn account_key(&self, index: usize) -> String {
+        self.transaction
+            .message
+            .account_keys
+            .get(index)
+            .map(|key| key.pubkey.clone())
+            .unwrap_or_default()
+    }
+
+    /// The accounts whose SOL balance changed, in the order of the account keys
+    pub fn sol_balance_changes(&self) -> Vec<SolBalanceChange> {
+        let Some(meta) = self.meta.as_ref() else {
+            return Vec::default();
+        };
+
+        meta.pre_balances
+            .iter()
+            .zip(meta.post_balances.iter())
+            .enumerate()
+            .filter(|(_, (pre, post))| pre != post)
+            .map(|(index, (pre, post))| SolBalanceChange {
+                account: self.account_key(index),
+                pre: *pre,
+                post: *post,
+            })
+            .collect()
+    }
+
+    /// The token accounts whose balance changed. Accounts created by the
+    /// transaction have no pre balance and closed accounts no post balance.
+    pub fn token_balance_changes(&self) -> Vec<TokenBalanceChange> {
+        let Some(meta) = self.meta.as_ref() else {
+            return Vec::default();
+        };
+
+        let mut changes = BTreeMap::<usize, TokenBalanceChange>::new();
+
+        let mut add_balance = |balance: &TokenBalance, is_post: bool| {
+            let amount = balance
+                .ui_token_amount
+                .amount
+                .parse::<u128>()
+                .unwrap_or_default();
+
+            let change =
+                changes
+                    .entry(balance.account_index)
+                    .or_insert_with(|| TokenBalanceChange {
+                        account: self.account_key(balance.account_index),
+                        mint: balance.mint.clone(),
+                        owner: balance.owner.clone(),
+                        decimals: balance.ui_token_amount.decimals,
+                        pre: 0,
+                        post: 0,
+                    });
+
+            if is_post {
+                change.post = amount;
+            } else {
+                change.pre = amount;
+            }
+        };
+
+        meta.pre_token_balances
+            .iter()
+            .for_each(|balance| add_balance(balance, false));
+        meta.post_token_balances
+            .iter()
+            .for_each(|balance| add_balance(balance, true));
+
+        changes
+            .into_values()
+            .filter(|change| change.pre != change.post)
+            .collect()
+    }
+
+    /// The instructions of the message, each followed by the
+    /// instructions it invoked
+    pub fn instructions(&self) -> Vec<DecodedInstruction> {
+        let inner_instructions = self
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.inner_instructions.as_ref());
+
+        self.transaction
+            .message
+            .instructions
+            .iter()
+            .enumerate()
+            .map(|(index, instruction)| {
+                let mut decoded = instruction.decode();
+
+                decoded.inner = inner_instructions
+                    .and_then(|inner| inner.iter().find(|inner| inner.index == index))
+                    .map(|inner| {
+                        inner
+                            .instructions
+                            .iter()
+                            .map(ParsedInstruction::decode)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                decoded
+            })
+            .collect()
+    }
+}
+
+/// `"legacy"` or the number of a versioned transaction
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum TransactionVersion {
+    Number(u8),
+    Legacy(String),
+}
+
+impl core::fmt::Display for TransactionVersion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Number(version) => write!(f, "V{version}"),
+            Self::Legacy(_) => write!(f, "LEGACY"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionMeta {
+    pub err: Option<TransactionError>,
+    pub fee: u64,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+    #[serde(default)]
+    pub pre_token_balances: Vec<TokenBalance>,
+    #[serde(default)]
+    pub post_token_balances: Vec<TokenBalance>,
+    #[serde(default)]
+    pub log_messages: Option<Vec<String>>,
+    #[serde(default)]
+    pub compute_units_consumed: Option<u64>,
+    #[serde(default)]
+    pub inner_instructions: Option<Vec<InnerInstructions>>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenBalance {
+    pub account_index: usize,
+    pub mint: String,
+    #[serde(default)]
+    pub owner: Option<String>,
+    pub ui_token_amount: UiTokenAmount,
+}
+
+/// The instructions invoked by the instruction at `index`
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InnerInstructions {
+    pub index: usize,
+    pub instructions: Vec<ParsedInstruction>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedTransaction {
+    pub signatures: Vec<String>,
+    pub message: ParsedMessage,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedMessage {
+    /// Includes the accounts loaded from lookup tables
+    pub account_keys: Vec<ParsedAccountKey>,
+    pub recent_blockhash: String,
+    pub instructions: Vec<ParsedInstruction>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedAccountKey {
+    pub pubkey: String,
+    pub signer: bool,
+    pub writable: bool,
+    /// `transaction` or `lookupTable`
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// The node parses the instructions of the programs it knows,
+/// the others keep their accounts and base58 data
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ParsedInstruction {
+    #[serde(rename_all = "camelCase")]
+    Parsed {
+        program: String,
+        program_id: String,
+        parsed: Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    PartiallyDecoded {
+        program_id: String,
+        accounts: Vec<String>,
+        data: String,
+    },
+}
+
+impl ParsedInstruction {
+    pub fn program_id(&self) -> &str {
+        match self {
+            Self::Parsed { program_id, .. } | Self::PartiallyDecoded { program_id, .. } => {
+                program_id.as_str()
+            }
+        }
+    }
+
+    pub fn decode(&self) -> DecodedInstruction {
+        let program_id = self.program_id().to_string();
+
+        match self {
+            // The memo program is parsed into the memo itself
+            Self::Parsed {
+                parsed: Value::String(memo),
+                ..
+            } => DecodedInstruction::new(&program_id, "Memo").add_field("Memo", memo),
+            Self::Parsed {
+                program, parsed, ..
+            } => {
+                let name = parsed
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .map(title_case)
+                    .unwrap_or(program.clone());
+
+                let fields = parsed.get("info").map(parsed_fields).unwrap_or_default();
+
+                DecodedInstruction {
+                    fields,
+                    ..DecodedInstruction::new(&program_id, &name)
+                }
+            }
+            Self::PartiallyDecoded { data, .. } if program_id == COMPUTE_BUDGET_PROGRAM_ID => {
+                decode_compute_budget(data)
+                    .unwrap_or(DecodedInstruction::new(&program_id, "Unknown Instruction"))
+            }
+            Self::PartiallyDecoded { accounts, data, .. } => {
+                DecodedInstruction::new(&program_id, "Unknown Instruction")
+                    .add_field("Accounts", &accounts.len().to_string())
+                    .add_field("Data", data)
+            }
+        }
+    }
+}
+
+/// Compute budget instructions are a one byte tag followed by a little endian integer
+fn decode_compute_budget(data: &str) -> Option<DecodedInstruction> {
+    let data = bs58::decode(data).into_vec().ok()?;
+    let (tag, value) = data.split_first()?;
+
+    let read_u32 = || Some(u32::from_le_bytes(value.get(..4)?.try_into().ok()?));
+    let instruction = |name: &str| DecodedInstruction::new(COMPUTE_BUDGET_PROGRAM_ID, name);
+
+    let decoded = match tag {
+        1 => instruction("Request Heap Frame").add_field("Bytes", &read_u32()?.to_string()),
+        2 => instruction("Set Compute Unit Limit").add_field("Units", &read_u32()?.to_string()),
+        3 => {
+            let price = u64::from_le_bytes(value.get(..8)?.try_into().ok()?);
+            instruction("Set Compute Unit Price")
+                .add_field("Micro-lamports per unit", &price.to_string())
+        }
+        4 => instruction("Set Loaded Accounts Data Size Limit")
+            .add_field("Bytes", &read_u32()?.to_string()),
+        _ => return None,
+    };
+
+    Some(decoded)
+}
+
+/// Flattens the `info` of a `jsonParsed` instruction or account into
+/// named values. Nested objects prefix the names of their fields.
+pub(crate) fn parsed_fields(info: &Value) -> Vec<(String, String)> {
+    let mut fields = Vec::<(String, String)>::new();
+    add_parsed_fields("", info, &mut fields);
+
+    fields
+}
+
+fn add_parsed_fields(prefix: &str, value: &Value, fields: &mut Vec<(String, String)>) {
+    let Some(object) = value.as_object() else {
+        return;
+    };
+
+    for (key, value) in object {
+        let name = String::new() + prefix + &title_case(key);
+
+        match value {
+            Value::Object(object) if !object.contains_key("uiAmountString") => {
+                add_parsed_fields(&(name + " "), value, fields)
+            }
+            value => fields.push((name, format_field(key, value))),
+        }
+    }
+}
+
+/// `lamports` is shown in SOL and token amounts by their UI amount
+fn format_field(key: &str, value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        Value::Null => String::from("None"),
+        Value::Number(lamports) if key == "lamports" => lamports
+            .as_u64()
+            .map(|lamports| format_sol(lamports) + " SOL")
+            .unwrap_or(lamports.to_string()),
+        Value::Object(object) => object
+            .get("uiAmountString")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        Value::Array(values) if values.iter().all(Value::is_string) => values
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<&str>>()
+            .join(", "),
+        // Vote histories and the like are too long to be useful here
+        Value::Array(values) => format!("{} entries", values.len()),
+        value => value.to_string(),
+    }
+}
+
+/// `transferChecked` becomes `Transfer Checked`
+pub(crate) fn title_case(value: &str) -> String {
+    let mut title = String::new();
+
+    for (index, character) in value.chars().enumerate() {
+        if index == 0 {
+            title.extend(character.to_uppercase());
+        } else if character.is_uppercase() {
+            title.push(' ');
+            title.push(character);
+        } else {
+            title.push(character);
+        }
+    }
+
+    title
+}
+
+/// An instruction ready to be displayed
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct DecodedInstruction {
+    pub program_id: String,
+    pub name: String,
+    pub fields: Vec<(String, String)>,
+    /// The instructions invoked by this one
+    pub inner: Vec<DecodedInstruction>,
+}
+
+impl DecodedInstruction {
+    pub fn new(program_id: &str, name: &str) -> Self {
+        Self {
+            program_id: program_id.to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn add_field(mut self, name: &str, value: &str) -> Self {
+        self.fields.push((name.to_string(), value.to_string()));
+
+        self
+    }
+
+    pub fn program_name(&self) -> String {
+        program_name(&self.program_id)
+    }
+}
+
+/// The name of the program if it is known, otherwise its id
+pub fn program_name(program_id: &str) -> String {
+    match program_id {
+        SYSTEM_PROGRAM_ID => "System Program".to_string(),
+        TOKEN_PROGRAM_ID => "Token Program".to_string(),
+        TOKEN_2022_PROGRAM_ID => "Token-2022 Program".to_string(),
+        MEMO_PROGRAM_ID | MEMO_V1_PROGRAM_ID => "Memo Program".to_string(),
+        COMPUTE_BUDGET_PROGRAM_ID => "Compute Budget Program".to_string(),
+        program_id => program_id.to_string(),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SolBalanceChange {
+    pub account: String,
+    pub pre: u64,
+    pub post: u64,
+}
+
+impl SolBalanceChange {
+    /// The signed change in SOL, eg. `-0.000005`
+    pub fn change(&self) -> String {
+        format_signed_amount(self.post as i128 - self.pre as i128, SOL_DECIMALS)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TokenBalanceChange {
+    pub account: String,
+    pub mint: String,
+    pub owner: Option<String>,
+    pub decimals: u8,
+    pub pre: u128,
+    pub post: u128,
+}
+
+impl TokenBalanceChange {
+    /// The signed change in tokens, eg. `+12.5`
+    pub fn change(&self) -> String {
+        format_signed_amount(self.post as i128 - self.pre as i128, self.decimals)
+    }
+}
+
+/// Formats a raw amount with `decimals` without going through floats
+fn format_signed_amount(amount: i128, decimals: u8) -> String {
+    let sign = if amount < 0 { "-" } else { "+" };
+
+    String::from(sign) + &format_decimal(amount.unsigned_abs(), decimals)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{block_on, MockTransport, RpcFixture};
+
+    const SIGNATURE: &str =
+        "5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv";
+    const FIXTURES: &str = include_str!("../fixtures/rpc/accounts.json");
+
+    fn load() -> TransactionResponse {
+        let client = RpcClient::with_transport(MockTransport::from_json(FIXTURES).unwrap());
+
+        block_on(load_transaction(&client, SIGNATURE))
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn reads_fee_status_and_balance_changes() {
+        let transaction = load();
+
+        assert_eq!(transaction.signature(), SIGNATURE);
+        assert_eq!(transaction.version, Some(TransactionVersion::Number(0)));
+        assert_eq!(transaction.fee(), Some(5200));
+        assert_eq!(transaction.error(), None);
+        assert_eq!(transaction.log_messages().len(), 14);
+
+        let sol_changes = transaction.sol_balance_changes();
+        assert_eq!(sol_changes.len(), 2);
+        assert_eq!(sol_changes[0].change(), "-0.0010052");
+        assert_eq!(sol_changes[1].change(), "+0.001");
+
+        let token_changes = transaction.token_balance_changes();
+        assert_eq!(token_changes.len(), 2);
+        assert_eq!(
+            token_changes[0].account,
+            "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T"
+        );
+        assert_eq!(token_changes[0].change(), "-2.5");
+        assert_eq!(token_changes[1].change(), "+2.5");
+    }
+
+    #[test]
+    fn decodes_known_instructions() {
+        let instructions = load().instructions();
+
+        assert_eq!(
+            instructions[0],
+            DecodedInstruction::new(COMPUTE_BUDGET_PROGRAM_ID, "Set Compute Unit Limit")
+                .add_field("Units", "200000")
+        );
+        assert_eq!(
+            instructions[1],
+            DecodedInstruction::new(COMPUTE_BUDGET_PROGRAM_ID, "Set Compute Unit Price")
+                .add_field("Micro-lamports per unit", "1000")
+        );
+
+        assert_eq!(instructions[2].name, "Transfer");
+        assert_eq!(instructions[2].program_name(), "System Program");
+        assert!(instructions[2]
+            .fields
+            .contains(&("Lamports".to_string(), "0.001 SOL".to_string())));
+
+        assert_eq!(instructions[3].name, "Transfer Checked");
+        assert!(instructions[3]
+            .fields
+            .contains(&("Token Amount".to_string(), "2.5".to_string())));
+
+        assert_eq!(
+            instructions[4],
+            DecodedInstruction::new(MEMO_PROGRAM_ID, "Memo").add_field("Memo", "Rent for March")
+        );
+    }
+
+    #[test]
+    fn unknown_transactions_are_none() {
+        let client = RpcClient::with_transport(MockTransport::replay(vec![RpcFixture {
+            method: "getTransaction".to_string(),
+            params: json!([
+                "unknown",
+                {
+                    "commitment": "confirmed",
+                    "encoding": "jsonParsed",
+                    "maxSupportedTransactionVersion": 0
+                }
+            ]),
+            response: json!({ "jsonrpc": "2.0", "result": null }),
+        }]));
+
+        assert_eq!(block_on(load_transaction(&client, "unknown")), Ok(None));
+    }
+}
@@ -0,0 +1,608 @@
+use std::str::FromStr;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::Deserialize;
+use serde_json::Value;
+use solana_sdk::{
+    hash::Hash, instruction::InstructionError, message::VersionedMessage, pubkey::Pubkey,
+    transaction::VersionedTransaction,
+};
+use solana_transaction_error::TransactionError;
+use wallet_adapter::{WalletError, WalletResult};
+
+use crate::{
+    durable_nonce, loaded_addresses, program_name, sign_with_wallet, submit_transaction,
+    AccountInfo, AccountInfoConfig, CommitmentConfig, Encoding, JsonParsedData, Lamports,
+    ResponseWithContext, RpcBatch, RpcCall, RpcClient, RpcError, RpcResult, RpcTransport,
+    SimulateAccountsConfig, SimulateTransactionConfig, SolBalanceChange, TokenBalanceChange,
+    UiTokenAmount, SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+
+/// What happens to a transaction once the user approves its preview
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ReviewAction {
+    /// Signed and sent, then rebroadcast until `last_valid_block_height`
+    Send { last_valid_block_height: u64 },
+    /// Only signed by the wallet
+    Sign,
+}
+
+/// A transaction waiting for the user to review its simulation
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PreparedTransaction {
+    pub tx: VersionedTransaction,
+    pub description: String,
+    pub action: ReviewAction,
+}
+
+impl PreparedTransaction {
+    pub fn send(tx: VersionedTransaction, last_valid_block_height: u64, description: &str) -> Self {
+        Self {
+            tx,
+            description: description.to_string(),
+            action: ReviewAction::Send {
+                last_valid_block_height,
+            },
+        }
+    }
+
+    pub fn sign(tx: VersionedTransaction, description: &str) -> Self {
+        Self {
+            tx,
+            description: description.to_string(),
+            action: ReviewAction::Sign,
+        }
+    }
+}
+
+/// The outcome of the [ReviewAction] of an approved transaction
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ApprovedTransaction {
+    Sent {
+        signature: String,
+    },
+    /// The signed transaction is kept so it can be sent later, eg. once the
+    /// other signers of a durable transaction approved it
+    Signed {
+        tx: VersionedTransaction,
+    },
+}
+
+impl ApprovedTransaction {
+    /// The base64 wire form of a signed transaction, as `sendTransaction` expects it
+    pub fn wire_transaction(&self) -> Option<String> {
+        match self {
+            Self::Sent { .. } => None,
+            Self::Signed { tx } => bincode::serialize(tx)
+                .ok()
+                .map(|bytes| BASE64_STANDARD.encode(bytes)),
+        }
+    }
+}
+
+/// Runs the [ReviewAction] of an approved transaction
+pub async fn approve_transaction(
+    prepared: &PreparedTransaction,
+) -> WalletResult<ApprovedTransaction> {
+    match prepared.action {
+        ReviewAction::Send {
+            last_valid_block_height,
+        } => submit_transaction(
+            &RpcClient::new(),
+            &prepared.tx,
+            last_valid_block_height,
+            &prepared.description,
+        )
+        .await
+        .map(|signature| ApprovedTransaction::Sent { signature }),
+        ReviewAction::Sign => {
+            let signed_tx = sign_with_wallet(&prepared.tx).await?;
+
+            // Wallets return legacy or versioned bytes depending on the message
+            bincode::deserialize::<VersionedTransaction>(&signed_tx)
+                .map(|tx| ApprovedTransaction::Signed { tx })
+                .map_err(|error| WalletError::Op(error.to_string()))
+        }
+    }
+}
+
+/// The result of `simulateTransaction`
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationResult {
+    pub err: Option<TransactionError>,
+    #[serde(default)]
+    pub logs: Option<Vec<String>>,
+    /// The accounts requested in the config as they are after the simulation
+    #[serde(default)]
+    pub accounts: Option<Vec<Option<AccountInfo<JsonParsedData>>>>,
+    #[serde(default)]
+    pub units_consumed: Option<u64>,
+}
+
+/// What a transaction would do if it was sent now
+#[derive(Debug, PartialEq, Clone)]
+pub struct TransactionPreview {
+    /// `None` if the node no longer knows the blockhash of the message
+    pub fee: Option<Lamports>,
+    /// The decoded simulation error, the transaction would fail
+    pub error: Option<String>,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+    /// The SOL balance of the fee payer
+    pub sol_changes: Vec<SolBalanceChange>,
+    /// The writable token accounts owned by the fee payer
+    pub token_changes: Vec<TokenBalanceChange>,
+}
+
+impl TransactionPreview {
+    pub fn can_approve(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+type AccountSnapshots = ResponseWithContext<Vec<Option<AccountInfo<JsonParsedData>>>>;
+
+/// Simulates `tx` with the latest blockhash and reads its fee. The writable
+/// accounts, including those loaded from lookup tables, are loaded before
+/// and after the simulation, all in one batch.
+pub async fn preview_transaction<T: RpcTransport>(
+    client: &RpcClient<T>,
+    tx: &VersionedTransaction,
+) -> RpcResult<TransactionPreview> {
+    let message = &tx.message;
+    let payer = message
+        .static_account_keys()
+        .first()
+        .map(Pubkey::to_string)
+        .unwrap_or_default();
+    let writable = message
+        .static_account_keys()
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| message.is_maybe_writable(*index, None))
+        .map(|(_, address)| address)
+        .chain(loaded_addresses(client, message).await?.writable.iter())
+        .map(Pubkey::to_string)
+        .collect::<Vec<String>>();
+
+    let encode = |bytes: Result<Vec<u8>, bincode::Error>| {
+        bytes
+            .map(|bytes| BASE64_STANDARD.encode(bytes))
+            .map_err(|error| RpcError::InvalidParams(error.to_string()))
+    };
+
+    // The node only prices messages with a blockhash it still knows, a durable
+    // transaction carries its nonce instead so it is priced with the latest one
+    let mut fee_message = message.clone();
+    if durable_nonce(message).is_some() {
+        let latest = client
+            .get_latest_blockhash(CommitmentConfig::default())
+            .await?;
+        fee_message.set_recent_blockhash(
+            Hash::from_str(&latest.blockhash)
+                .map_err(|error| RpcError::Deserialize(error.to_string()))?,
+        );
+    }
+
+    let mut batch = RpcBatch::new();
+
+    let simulation = batch.add(RpcCall::simulate_transaction(
+        &encode(bincode::serialize(tx))?,
+        SimulateTransactionConfig {
+            sig_verify: Some(false),
+            replace_recent_blockhash: Some(true),
+            accounts: Some(SimulateAccountsConfig {
+                addresses: writable.clone(),
+                encoding: Encoding::JsonParsed,
+            }),
+            ..Default::default()
+        },
+    ));
+    let fee = batch.add(RpcCall::get_fee_for_message(
+        &encode(bincode::serialize(&fee_message))?,
+        CommitmentConfig::default(),
+    ));
+    let accounts = batch.add(RpcCall::<AccountSnapshots>::get_multiple_accounts(
+        &writable,
+        AccountInfoConfig::new(Encoding::JsonParsed),
+    ));
+
+    let mut responses = client.send_batch(batch).await?;
+
+    let simulation = responses.take(simulation)?.value;
+    let fee = responses.take(fee)?.value.map(Lamports::new);
+    let pre_accounts = responses.take(accounts)?.value;
+    let post_accounts = simulation.accounts.unwrap_or_default();
+
+    let mut sol_changes = Vec::<SolBalanceChange>::new();
+    let mut token_changes = Vec::<TokenBalanceChange>::new();
+
+    for (index, address) in writable.into_iter().enumerate() {
+        let pre = pre_accounts.get(index).cloned().flatten();
+        let post = post_accounts.get(index).cloned().flatten();
+
+        if address == payer {
+            sol_changes.push(SolBalanceChange {
+                account: address.clone(),
+                pre: pre
+                    .as_ref()
+                    .map(|account| account.lamports)
+                    .unwrap_or_default(),
+                post: post
+                    .as_ref()
+                    .map(|account| account.lamports)
+                    .unwrap_or_default(),
+            });
+        }
+
+        let pre_token = pre.as_ref().and_then(ParsedTokenAccount::from_account);
+        let post_token = post.as_ref().and_then(ParsedTokenAccount::from_account);
+
+        let Some(token) = post_token.as_ref().or(pre_token.as_ref()) else {
+            continue;
+        };
+        if token.owner != payer {
+            continue;
+        }
+
+        token_changes.push(TokenBalanceChange {
+            account: address,
+            mint: token.mint.clone(),
+            owner: Some(token.owner.clone()),
+            decimals: token.token_amount.decimals,
+            pre: pre_token
+                .as_ref()
+                .map(ParsedTokenAccount::amount)
+                .unwrap_or_default(),
+            post: post_token
+                .as_ref()
+                .map(ParsedTokenAccount::amount)
+                .unwrap_or_default(),
+        });
+    }
+
+    Ok(TransactionPreview {
+        fee,
+        error: simulation
+            .err
+            .as_ref()
+            .map(|error| describe_error(error, message)),
+        units_consumed: simulation.units_consumed,
+        logs: simulation.logs.unwrap_or_default(),
+        sol_changes,
+        token_changes,
+    })
+}
+
+/// The `info` of a `jsonParsed` token account
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ParsedTokenAccount {
+    mint: String,
+    owner: String,
+    token_amount: UiTokenAmount,
+}
+
+impl ParsedTokenAccount {
+    fn from_account(account: &AccountInfo<JsonParsedData>) -> Option<Self> {
+        let JsonParsedData::Parsed(data) = &account.data else {
+            return None;
+        };
+        if data.parsed.get("type").and_then(Value::as_str) != Some("account") {
+            return None;
+        }
+
+        serde_json::from_value(data.parsed.get("info")?.clone()).ok()
+    }
+
+    fn amount(&self) -> u128 {
+        self.token_amount.amount.parse().unwrap_or_default()
+    }
+}
+
+/// Names the instruction and program that failed and the meaning
+/// of custom errors of the programs the app sends instructions to
+pub fn describe_error(error: &TransactionError, message: &VersionedMessage) -> String {
+    let TransactionError::InstructionError(index, instruction_error) = error else {
+        return error.to_string();
+    };
+
+    // Programs cannot be loaded from lookup tables
+    let program_id = message
+        .instructions()
+        .get(*index as usize)
+        .and_then(|instruction| {
+            message
+                .static_account_keys()
+                .get(instruction.program_id_index as usize)
+        })
+        .map(Pubkey::to_string)
+        .unwrap_or_default();
+
+    let reason = match instruction_error {
+        InstructionError::Custom(code) => custom_error(&program_id, *code)
+            .map(str::to_string)
+            .unwrap_or(instruction_error.to_string()),
+        _ => instruction_error.to_string(),
+    };
+
+    format!(
+        "Instruction #{} ({}) failed: {reason}",
+        *index as usize + 1,
+        program_name(&program_id)
+    )
+}
+
+fn custom_error(program_id: &str, code: u32) -> Option<&'static str> {
+    let message = match (program_id, code) {
+        (TOKEN_PROGRAM_ID | TOKEN_2022_PROGRAM_ID, code) => match code {
+            0 => "Lamport balance below rent-exempt threshold",
+            1 => "Insufficient funds",
+            2 => "Invalid Mint",
+            3 => "Account not associated with this Mint",
+            4 => "Owner does not match",
+            5 => "Fixed supply",
+            6 => "Already in use",
+            7 => "Invalid number of provided signers",
+            8 => "Invalid number of required signers",
+            9 => "State is uninitialized",
+            10 => "Instruction does not support native tokens",
+            11 => "Non-native account can only be closed if its balance is zero",
+            12 => "Invalid instruction",
+            13 => "State is invalid for requested operation",
+            14 => "Operation overflowed",
+            15 => "Account does not support specified authority type",
+            16 => "This token mint cannot freeze accounts",
+            17 => "Account is frozen",
+            18 => "The provided decimals value different from the Mint decimals",
+            _ => return None,
+        },
+        (SYSTEM_PROGRAM_ID, code) => match code {
+            0 => "An account with the same address already exists",
+            1 => "Account does not have enough SOL to perform the operation",
+            2 => "Cannot assign account to this program id",
+            3 => "Cannot allocate account data of this length",
+            4 => "Length of requested seed is too long",
+            5 => "Provided address does not match addressed derived from seed",
+            6 => "Advancing stored nonce requires a populated RecentBlockhashes sysvar",
+            7 => "Stored nonce is still in recent_blockhashes",
+            8 => "Specified nonce does not match stored nonce",
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use solana_sdk::system_instruction::{advance_nonce_account, transfer};
+
+    use super::*;
+    use crate::{
+        block_on, system_account, token_account, transfer_checked, versioned_transaction,
+        MockTransport, RpcFixture, TokenAmount, TokenProgram, MINT, OWNER, RECIPIENT, SOURCE,
+    };
+
+    const DESTINATION: &str = "FzT7dYmKZnUgnMkXpXsJx6UjLbfbMdpXZxQpr3qVY2qB";
+
+    fn pubkey(address: &str) -> Pubkey {
+        Pubkey::from_str(address).unwrap()
+    }
+
+    /// Sends 0.001 SOL and 2.5 tokens, the simulation sees the accounts as `post`
+    fn client(tx: &VersionedTransaction, error: Value) -> RpcClient<MockTransport> {
+        let writable = tx
+            .message
+            .static_account_keys()
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| tx.message.is_maybe_writable(*index, None))
+            .map(|(_, address)| address.to_string())
+            .collect::<Vec<String>>();
+        let accounts = |post: bool| {
+            writable
+                .iter()
+                .map(|address| match (address.as_str(), post) {
+                    (OWNER, false) => system_account(1_000_000_000),
+                    (OWNER, true) => system_account(998_995_000),
+                    (RECIPIENT, false) => Value::Null,
+                    (RECIPIENT, true) => system_account(1_000_000),
+                    (SOURCE, false) => token_account(OWNER, 12_500_000),
+                    (SOURCE, true) => token_account(OWNER, 10_000_000),
+                    (_, false) => token_account(RECIPIENT, 0),
+                    (_, true) => token_account(RECIPIENT, 2_500_000),
+                })
+                .collect::<Vec<Value>>()
+        };
+        let context = json!({ "slot": 1 });
+
+        RpcClient::with_transport(MockTransport::replay(vec![
+            RpcFixture {
+                method: "simulateTransaction".to_string(),
+                params: json!([
+                    BASE64_STANDARD.encode(bincode::serialize(tx).unwrap()),
+                    {
+                        "sigVerify": false,
+                        "replaceRecentBlockhash": true,
+                        "encoding": "base64",
+                        "accounts": { "addresses": writable, "encoding": "jsonParsed" }
+                    }
+                ]),
+                response: json!({
+                    "jsonrpc": "2.0",
+                    "result": {
+                        "context": context,
+                        "value": {
+                            "err": error,
+                            "logs": ["Program 11111111111111111111111111111111 invoke [1]"],
+                            "accounts": accounts(true),
+                            "unitsConsumed": 6350
+                        }
+                    }
+                }),
+            },
+            RpcFixture {
+                method: "getFeeForMessage".to_string(),
+                params: json!([
+                    BASE64_STANDARD.encode(bincode::serialize(&tx.message).unwrap()),
+                    {}
+                ]),
+                response: json!({
+                    "jsonrpc": "2.0",
+                    "result": { "context": context, "value": 5000 }
+                }),
+            },
+            RpcFixture {
+                method: "getMultipleAccounts".to_string(),
+                params: json!([writable, { "encoding": "jsonParsed" }]),
+                response: json!({
+                    "jsonrpc": "2.0",
+                    "result": { "context": context, "value": accounts(false) }
+                }),
+            },
+        ]))
+    }
+
+    fn transaction() -> VersionedTransaction {
+        let owner = pubkey(OWNER);
+        let instructions = [
+            transfer(&owner, &pubkey(RECIPIENT), 1_000_000),
+            transfer_checked(
+                TokenProgram::Token,
+                &pubkey(SOURCE),
+                &pubkey(MINT),
+                &pubkey(DESTINATION),
+                &owner,
+                TokenAmount::new(2_500_000, 6),
+            ),
+        ];
+
+        versioned_transaction(&owner, &instructions, &[], Hash::default()).unwrap()
+    }
+
+    #[test]
+    fn previews_fee_and_balance_changes() {
+        let tx = transaction();
+        let preview = block_on(preview_transaction(&client(&tx, Value::Null), &tx)).unwrap();
+
+        assert!(preview.can_approve());
+        assert_eq!(preview.fee, Some(Lamports::new(5000)));
+        assert_eq!(preview.units_consumed, Some(6350));
+        assert_eq!(preview.logs.len(), 1);
+
+        // Only the accounts of the fee payer are previewed
+        assert_eq!(
+            preview.sol_changes,
+            [SolBalanceChange {
+                account: OWNER.to_string(),
+                pre: 1_000_000_000,
+                post: 998_995_000,
+            }]
+        );
+        assert_eq!(preview.token_changes.len(), 1);
+        assert_eq!(preview.token_changes[0].account, SOURCE);
+        assert_eq!(preview.token_changes[0].change(), "-2.5");
+    }
+
+    #[test]
+    fn prices_durable_transactions_with_the_latest_blockhash() {
+        let owner = pubkey(OWNER);
+        let nonce_account = Pubkey::new_unique();
+        let instructions = [
+            advance_nonce_account(&nonce_account, &owner),
+            transfer(&owner, &pubkey(RECIPIENT), 1_000_000),
+        ];
+        let tx = versioned_transaction(&owner, &instructions, &[], Hash::new_unique()).unwrap();
+
+        let latest_blockhash = Hash::new_unique();
+        let mut message = tx.message.clone();
+        message.set_recent_blockhash(latest_blockhash);
+
+        let client = client(&tx, Value::Null);
+        let context = json!({ "slot": 1 });
+        let fee_for_message = |message: &VersionedMessage, fee: Value| RpcFixture {
+            method: "getFeeForMessage".to_string(),
+            params: json!([
+                BASE64_STANDARD.encode(bincode::serialize(message).unwrap()),
+                {}
+            ]),
+            response: json!({
+                "jsonrpc": "2.0",
+                "result": { "context": context, "value": fee }
+            }),
+        };
+        // The nonce is not a blockhash the node knows
+        client
+            .transport()
+            .insert(fee_for_message(&tx.message, Value::Null));
+        client
+            .transport()
+            .insert(fee_for_message(&message, json!(5000)));
+        client.transport().insert(RpcFixture {
+            method: "getLatestBlockhash".to_string(),
+            params: json!([{}]),
+            response: json!({
+                "jsonrpc": "2.0",
+                "result": {
+                    "context": context,
+                    "value": {
+                        "blockhash": latest_blockhash.to_string(),
+                        "lastValidBlockHeight": 200
+                    }
+                }
+            }),
+        });
+
+        let preview = block_on(preview_transaction(&client, &tx)).unwrap();
+        assert_eq!(preview.fee, Some(Lamports::new(5000)));
+    }
+
+    #[test]
+    fn keeps_the_signed_transaction() {
+        let tx = transaction();
+        let wire_transaction = ApprovedTransaction::Signed { tx: tx.clone() }
+            .wire_transaction()
+            .unwrap();
+
+        let bytes = BASE64_STANDARD.decode(wire_transaction).unwrap();
+        assert_eq!(
+            bincode::deserialize::<VersionedTransaction>(&bytes).unwrap(),
+            tx
+        );
+
+        let sent = ApprovedTransaction::Sent {
+            signature: String::new(),
+        };
+        assert_eq!(sent.wire_transaction(), None);
+    }
+
+    #[test]
+    fn decodes_simulation_errors() {
+        let tx = transaction();
+        let error = json!({ "InstructionError": [1, { "Custom": 1 }] });
+        let preview = block_on(preview_transaction(&client(&tx, error), &tx)).unwrap();
+
+        assert!(!preview.can_approve());
+        assert_eq!(
+            preview.error.as_deref(),
+            Some("Instruction #2 (Token Program) failed: Insufficient funds")
+        );
+
+        assert_eq!(
+            describe_error(
+                &TransactionError::InstructionError(0, InstructionError::Custom(1)),
+                &tx.message
+            ),
+            "Instruction #1 (System Program) failed: \
+             Account does not have enough SOL to perform the operation"
+        );
+        assert_eq!(
+            describe_error(&TransactionError::AccountNotFound, &tx.message),
+            TransactionError::AccountNotFound.to_string()
+        );
+    }
+}
@@ -2,7 +2,7 @@ use dioxus::prelude::*;
 use qrcodegen::{QrCode, QrCodeEcc};
 use wallet_adapter::{wasm_bindgen_futures::JsFuture, Cluster, WalletResult};
 
-use crate::{DevnetSvg, LocalnetSvg, MainnetSvg, TestnetSvg, CLUSTER_STORAGE, WINDOW};
+use crate::{DevnetSvg, LocalnetSvg, MainnetSvg, Route, TestnetSvg, CLUSTER_STORAGE, WINDOW};
 
 pub fn trunk_cluster_name(name: &str) -> String {
     if name.len() > 10 {
@@ -48,6 +48,37 @@ pub(crate) fn link_target_blank(href: &str, text: &str) -> Element {
     rsx! {a {class:"underline", href, target:"_blank", rel:"noopener noreferrer", {text}"⇗"}}
 }
 
+/// Links `address` to the account page, the explorer only sees public clusters
+/// so it is the secondary link
+pub(crate) fn address_link(address: &str, text: &str) -> Element {
+    rsx! {
+        span {class:"inline-flex items-center",
+            Link {class:"underline", to: Route::AccountDetail { pubkey: address.to_string() }, {text}}
+            a {class:"ml-1 opacity-70 hover:opacity-100", href: format_address_url(address), target:"_blank", rel:"noopener noreferrer", title:"View in explorer", "⇗"}
+        }
+    }
+}
+
+/// Links a signature to its [Route::TransactionDetail] page,
+/// the explorer stays reachable through the ⇗ link next to it
+pub(crate) fn signature_link(signature: &str, text: &str) -> Element {
+    rsx! {
+        span {class:"inline-flex items-center",
+            Link {class:"underline", to: Route::TransactionDetail { signature: signature.to_string() }, {text}}
+            a {class:"ml-1 opacity-70 hover:opacity-100", href: format_tx_url(signature), target:"_blank", rel:"noopener noreferrer", title:"View in explorer", "⇗"}
+        }
+    }
+}
+
+/// [address_link] with the address shortened as its text
+pub(crate) fn shortened_address_link(address: &str) -> Element {
+    let shortened_address = wallet_adapter::Utils::shorten_base58(address)
+        .map(|address| address.to_string())
+        .unwrap_or(address.to_string());
+
+    address_link(address, &shortened_address)
+}
+
 pub async fn copied_address(address: &str) -> WalletResult<()> {
     let pending: JsFuture = WINDOW
         .read()
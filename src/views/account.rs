@@ -0,0 +1,138 @@
+use dioxus::prelude::*;
+
+use crate::{
+    address_link, format_sol, link_target_blank, load_account_info, utils::format_address_url,
+    AccountData, AccountInfo, CancelHandle, DataEncoding, Loader, RpcClient, RpcError, RpcResult,
+    CLUSTER_STORAGE, MAX_DISPLAYED_BYTES,
+};
+
+/// An account of the active cluster, loaded from the cluster itself
+/// so accounts of localnet and private clusters can be inspected too
+#[component]
+pub fn AccountDetail(pubkey: String) -> Element {
+    let mut account = use_signal(|| Option::<RpcResult<Option<AccountInfo<AccountData>>>>::None);
+
+    // Aborts the request when the page is left before it answers
+    let cancel_handle = use_hook(CancelHandle::new);
+    let effect_cancel_handle = cancel_handle.clone();
+
+    use_effect(use_reactive(&pubkey, move |pubkey| {
+        // Reloads the account when the active cluster changes
+        let _ = CLUSTER_STORAGE.read();
+
+        effect_cancel_handle.cancel();
+        account.set(None);
+
+        let client = RpcClient::with_cancel_handle(effect_cancel_handle.clone());
+        spawn(async move {
+            match load_account_info(&client, &pubkey).await {
+                Err(RpcError::Cancelled) => {}
+                outcome => account.set(Some(outcome)),
+            }
+        });
+    }));
+
+    use_drop(move || cancel_handle.cancel());
+
+    let cluster_name = CLUSTER_STORAGE.read().active_cluster().name().to_string();
+
+    let body = match account.read().as_ref() {
+        None => rsx! {
+            div { class: "flex items-center", span{class:"mr-2", {Loader()}} "Loading account..." }
+        },
+        Some(Err(error)) => rsx! {
+            p { class: "text-red-500", "Failed to load the account: {error}" }
+        },
+        Some(Ok(None)) => rsx! {
+            p { "The account does not exist on {cluster_name}" }
+        },
+        Some(Ok(Some(account))) => rsx! {
+            AccountSummary { account: account.clone() }
+        },
+    };
+
+    rsx! {
+        div { class: "flex w-full flex-col items-center mt-4 mb-10 px-4",
+            div { class: "flex flex-col w-full lg:w-[80%] gap-4",
+                div { class: "flex flex-col w-full",
+                    h1 { class: "text-2xl text-true-blue dark:text-white", "Account" }
+                    span { class: "text-sm break-all", {pubkey.as_str()} }
+                    span { class: "text-xs mt-1", {link_target_blank(&format_address_url(&pubkey), "View in explorer")} }
+                }
+                {body}
+            }
+        }
+    }
+}
+
+#[component]
+fn AccountSummary(account: AccountInfo<AccountData>) -> Element {
+    let mut encoding = use_signal(DataEncoding::default);
+
+    let displayed_bytes = account.data_size().min(MAX_DISPLAYED_BYTES);
+    let encoded_data = encoding
+        .read()
+        .encode(&account.data.bytes[..displayed_bytes]);
+    let kind = account.kind();
+    let shortened_owner = wallet_adapter::Utils::shorten_base58(&account.owner)
+        .map(|owner| owner.to_string())
+        .unwrap_or(account.owner.clone());
+
+    rsx! {
+        div { class: "flex flex-col w-full rounded-lg p-5 bg-true-blue text-white gap-1",
+            div { class: "text-sm", "Balance: " {format_sol(account.lamports)} " SOL" }
+            div { class: "text-sm", "Owner: " {address_link(&account.owner, &shortened_owner)} }
+            div { class: "text-sm", "Executable: " if account.executable { "Yes" } else { "No" } }
+            div { class: "text-sm", "Rent epoch: {account.rent_epoch}" }
+            div { class: "text-sm", "Data size: {account.data_size()} bytes" }
+            if let Some(kind) = kind {
+                div { class: "flex",
+                    span { class: "bg-blue-100 text-blue-800 text-xs font-semibold px-2.5 py-0.5 rounded-full dark:bg-blue-200 dark:text-blue-800",
+                        {kind.to_string()}
+                    }
+                }
+            }
+        }
+
+        if kind.is_some() {
+            div { class: "flex flex-col w-full",
+                h2 { class: "text-xl text-true-blue dark:text-white mb-2", "Decoded Data" }
+                for (name, value) in account.decoded_fields() {
+                    div { class: "flex w-full justify-between text-sm border-b border-gray-500 py-1",
+                        span { class: "opacity-80 mr-4", {name} }
+                        span { class: "break-all text-right", {value} }
+                    }
+                }
+            }
+        }
+
+        div { class: "flex flex-col w-full",
+            div { class: "flex w-full items-center justify-between mb-2",
+                h2 { class: "text-xl text-true-blue dark:text-white", "Raw Data" }
+                div { class: "flex gap-2",
+                    for data_encoding in DataEncoding::ALL {
+                        button {
+                            class: if *encoding.read() == data_encoding {
+                                "text-xs px-3 py-1 rounded-full bg-true-blue text-white"
+                            } else {
+                                "text-xs px-3 py-1 rounded-full border border-true-blue"
+                            },
+                            onclick: move |_| encoding.set(data_encoding),
+                            {data_encoding.to_string()}
+                        }
+                    }
+                }
+            }
+            if account.data_size() == 0 {
+                p { class: "text-sm", "The account has no data" }
+            } else {
+                pre { class: "text-xs whitespace-pre-wrap break-all p-4 rounded-lg bg-gray-100 dark:bg-gray-800",
+                    {encoded_data}
+                }
+                if account.data_size() > displayed_bytes {
+                    p { class: "text-xs mt-1", "Showing the first {displayed_bytes} of {account.data_size()} bytes" }
+                }
+            }
+        }
+    }
+}
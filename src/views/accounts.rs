@@ -2,13 +2,16 @@ use dioxus::prelude::*;
 use wallet_adapter::Cluster;
 
 use crate::{
-    format_timestamp, link_target_blank, trunk_cluster_name,
-    utils::{format_address_url, format_tx_url, get_cluster_svg},
-    views::{ReceiveSol, SendSol, QueryAccountDialog},
-    Airdrop, AirdropSvg, AtaSvg, BalanceSvg, CheckSvg, ErrorSvg, Loader, MintSvg, NotificationInfo,
-    ReceiveSvg, SendSvg, SignatureSvg, SignaturesResponse, TimestampSvg, TokenAccountResponse,
-    UserSvg, WalletSvg, ACCOUNT_STATE, ACTIVE_CONNECTION, CLUSTER_NET_STATE, CLUSTER_STORAGE,
-    GLOBAL_MESSAGE, LOADING,
+    address_link, format_timestamp, trunk_cluster_name,
+    utils::get_cluster_svg,
+    views::{CloseEmptyAccounts, ReceiveSol, SendSol, SendToken, QueryAccountDialog},
+    empty_token_accounts, load_account_state, load_transactions_page, reclaimable_rent,
+    resolve_token_metadata, token_metadata, Airdrop, AirdropSvg, AtaSvg, BalanceSvg, CancelHandle, CheckSvg,
+    ErrorSvg, Lamports, Loader, MintSvg, NotificationInfo, PubSubClient, PubSubNotification,
+    ReceiveSvg, Route, RpcClient, RpcError, SendSvg, SignatureSvg, SignaturesResponse, Subscription,
+    TimestampSvg, TokenAccountResponse, TokenAmount, TokenExtension, TokenProgram, UserSvg,
+    WalletSvg, ACCOUNT_STATE, ACTIVE_CONNECTION, CLUSTER_NET_STATE, CLUSTER_SLOT, CLUSTER_STORAGE,
+    GLOBAL_MESSAGE, LOADING, PUBSUB, TRANSACTIONS_PAGE_SIZE,
 };
 
 use super::ConnectWalletFirst;
@@ -19,13 +22,19 @@ pub enum ClusterNetState {
     #[default]
     Waiting,
     Failure,
+    /// The cluster did not answer before the request timed out
+    Slow,
 }
 
 #[derive(Debug, Default, PartialEq)]
 pub struct AccountState {
-    pub balance: String,
+    pub balance: Lamports,
     pub token_accounts: Vec<TokenAccountResponse>,
     pub transactions: Vec<SignaturesResponse>,
+    /// The oldest signature loaded so far, the next page starts `before` it
+    pub history_cursor: Option<String>,
+    /// The last page was shorter than [TRANSACTIONS_PAGE_SIZE]
+    pub history_complete: bool,
 }
 
 impl AccountState {
@@ -34,7 +43,51 @@ impl AccountState {
     }
 
     pub fn transactions_is_empty(&self) -> bool {
-        self.token_accounts.is_empty()
+        self.transactions.is_empty()
+    }
+
+    /// Appends a page of `getSignaturesForAddress` and moves the cursor to its end
+    pub fn add_transactions_page(&mut self, page: Vec<SignaturesResponse>) -> &mut Self {
+        self.history_complete = page.len() < TRANSACTIONS_PAGE_SIZE;
+
+        if let Some(last) = page.last() {
+            self.history_cursor.replace(last.signature.clone());
+        }
+        self.transactions.extend(page);
+
+        self
+    }
+
+    /// Takes the balance and token accounts of a reload of the first page and
+    /// puts its signatures newer than the loaded ones in front, keeping the
+    /// older pages and the cursor. Without an overlap the reload replaces them.
+    pub fn merge_latest(&mut self, latest: AccountState) -> &mut Self {
+        self.balance = latest.balance;
+        self.token_accounts = latest.token_accounts;
+
+        let newest = self.transactions.first().map(|tx| tx.signature.as_str());
+        let overlap = latest
+            .transactions
+            .iter()
+            .position(|tx| Some(tx.signature.as_str()) == newest);
+
+        match overlap {
+            Some(position) => {
+                self.transactions
+                    .splice(0..0, latest.transactions.into_iter().take(position));
+            }
+            None => {
+                self.transactions = latest.transactions;
+                self.history_cursor = latest.history_cursor;
+                self.history_complete = latest.history_complete;
+            }
+        }
+
+        self
+    }
+
+    pub fn has_more_transactions(&self) -> bool {
+        !self.history_complete && self.history_cursor.is_some()
     }
 
     pub fn token_accounts(&self) -> &[TokenAccountResponse] {
@@ -72,6 +125,8 @@ pub fn Accounts() -> Element {
             }
         } else if *CLUSTER_NET_STATE.read() == ClusterNetState::Waiting {
             rsx! {"Loading account info..."}
+        } else if *CLUSTER_NET_STATE.read() == ClusterNetState::Slow {
+            rsx! {"CLUSTER NETWORK IS SLOW TO RESPOND"}
         } else {
             rsx! {"CLUSTER NETWORK UNREACHABLE"}
         }
@@ -90,45 +145,76 @@ fn ClusterSuccess(
     let mut show_query_dialog = use_signal(|| false);
     let mut show_airdrop_modal = use_signal(|| false);
     let mut show_receive_modal = use_signal(|| false);
+    let mut show_close_modal = use_signal(|| false);
     let mut refreshing = use_signal(|| false);
+    let mut loading_more = use_signal(|| false);
 
-    let check_balance = || {
-        let balance = ACCOUNT_STATE
-            .read()
-            .balance
-            .as_str()
-            .parse::<f64>()
-            .unwrap_or_default();
+    let check_balance = || ACCOUNT_STATE.read().balance.is_zero();
+    let empty_accounts = empty_token_accounts(ACCOUNT_STATE.read().token_accounts());
 
-        balance == f64::default()
-    };
+    // Aborts the account requests still running when the view is left
+    let cancel_handle = use_hook(CancelHandle::new);
 
     let clone_address = address.clone();
+    let effect_cancel_handle = cancel_handle.clone();
 
     use_effect(move || {
         *ACCOUNT_STATE.write() = AccountState::default();
         let clone_address = clone_address.clone();
+        let cancel_handle = effect_cancel_handle.clone();
 
         spawn(async move {
-            fetch_account_state(None, None, &clone_address).await;
+            fetch_account_state(None, None, &clone_address, &cancel_handle).await;
         });
     });
 
+    // Balance updates rerun this too, the cache keeps them from sending requests
+    use_effect(move || {
+        let token_accounts = ACCOUNT_STATE.read().token_accounts.clone();
+
+        spawn(async move {
+            resolve_token_metadata(token_accounts).await;
+        });
+    });
+
+    let pubsub_address = address.clone();
+
+    // Reconnects whenever the active cluster changes
+    use_effect(move || {
+        let ws_endpoint = CLUSTER_STORAGE.read().active_cluster().ws_endpoint();
+
+        connect_pubsub(&ws_endpoint, &pubsub_address);
+    });
+
+    let drop_cancel_handle = cancel_handle.clone();
+    let more_cancel_handle = cancel_handle.clone();
+    let more_address = address.clone();
+
+    use_drop(move || {
+        drop_cancel_handle.cancel();
+
+        if let Some(pubsub) = PUBSUB.write().take() {
+            pubsub.close();
+        }
+        CLUSTER_SLOT.write().take();
+    });
+
     rsx! {
         div {class:"flex w-full h-full mt-4 mb-10 flex-col items-center",
             div {
                 class:"shadow-sm p-5 w-full flex flex-col items-center mb-10 justify-center",
                 div{class:"text-center w-full",
                     if LOADING.read().is_none(){
-                        span{class:"text-3xl", {ACCOUNT_STATE.read().balance.as_str()} " SOL"}
+                        span{class:"text-3xl", {ACCOUNT_STATE.read().balance.to_string()} " SOL"}
                     }else {
                         span{class:"mr-2", {Loader()}}  span{class:"text-sm","Loading Balance..."}
                     }
                 }
                 div { class:"flex w-full items-center justify-center",
                     span {class:"flex w-[20px] mr-1", {WalletSvg()}}
-                    {link_target_blank(&format_address_url(&address), &shortened_address)}
+                    {address_link(&address, &shortened_address)}
                 }
+                LiveSlot {}
                 div {class:"w-full flex gap-4 flex-wrap items-center justify-center",
                     if !check_balance() {
                         button {
@@ -157,9 +243,10 @@ fn ClusterSuccess(
                     button{
                         onclick:move|_|{
                             let address = address.clone();
+                            let cancel_handle = cancel_handle.clone();
                             spawn(async move {
                                 refreshing.set(true);
-                                fetch_account_state(Some("REFRESHED ACCOUNTS"), Some("REFRESH ERROR"), &address).await ;
+                                fetch_account_state(Some("REFRESHED ACCOUNTS"), Some("REFRESH ERROR"), &address, &cancel_handle).await ;
                                 refreshing.set(false);
                             });
 
@@ -188,13 +275,25 @@ fn ClusterSuccess(
                         span{class:"mr-2", {Loader()}} "Loading Token Accounts..."
                     }
                 }
+                if LOADING.read().is_none() && !empty_accounts.is_empty() {
+                    button {
+                        onclick:move|_|{show_close_modal.set(true)},
+                        class:"flex bg-true-blue items-center justify-center text-sm text-white px-5 py-2 mt-3 rounded-full hover:bg-cobalt-blue",
+                        "Clean up {empty_accounts.len()} empty accounts · "
+                        {reclaimable_rent(&empty_accounts).to_string()} " SOL"
+                    }
+                }
 
                 for token_account in ACCOUNT_STATE.read().token_accounts() {
                     TokenAccountCard{
                         mint: token_account.mint(),
                         ata_address: token_account.ata_address(),
+                        owner: token_account.owner(),
                         token_balance: token_account.balance(),
-                        state: token_account.state()
+                        amount: token_account.amount(),
+                        state: token_account.state(),
+                        program: token_account.program(),
+                        extensions: token_account.extensions()
                     }
                 }
             }
@@ -217,7 +316,26 @@ fn ClusterSuccess(
                             tx: tx.signature.clone(),
                             timestamp: tx.block_time,
                             state: tx.confirmation_status.clone(),
-                            succeeded: tx.err.is_none(), address: &address}
+                            succeeded: tx.err.is_none(), address: &address,
+                            memos: tx.memos()}
+                    }
+                }
+                if LOADING.read().is_none() && ACCOUNT_STATE.read().has_more_transactions() {
+                    button{
+                        onclick:move|_|{
+                            let address = more_address.clone();
+                            let cancel_handle = more_cancel_handle.clone();
+                            spawn(async move {
+                                loading_more.set(true);
+                                fetch_more_transactions(&address, &cancel_handle).await;
+                                loading_more.set(false);
+                            });
+                        },
+                        disabled:*loading_more.read(),
+                        class:"flex items-center text-sm text-white px-5 py-2 mt-5 rounded-full bg-true-blue hover:bg-cobalt-blue",
+                        if *loading_more.read() {
+                            {Loader()}
+                        }else {"Load more"}
                     }
                 }
             }
@@ -226,6 +344,7 @@ fn ClusterSuccess(
         SendSol{show_send_modal}
 	QueryAccountDialog{show_query_dialog}
         ReceiveSol{show_receive_modal}
+        CloseEmptyAccounts{show_close_modal, public_key_bytes}
         if CLUSTER_STORAGE.read().active_cluster().cluster() != Cluster::MainNet{
             Airdrop{show_airdrop_modal}
         }
@@ -236,9 +355,15 @@ fn ClusterSuccess(
 pub fn TokenAccountCard(
     mint: String,
     ata_address: String,
+    owner: String,
     token_balance: String,
+    amount: TokenAmount,
     state: String,
+    program: TokenProgram,
+    extensions: Vec<TokenExtension>,
 ) -> Element {
+    let mut show_send_modal = use_signal(|| false);
+
     let cluster = CLUSTER_STORAGE.read().active_cluster().cluster();
     let cluster_image = get_cluster_svg(cluster);
 
@@ -250,6 +375,15 @@ pub fn TokenAccountCard(
     let shortened_ata_address = wallet_adapter::Utils::shorten_base58(&ata_address)
         .map(|address| address.to_string())
         .unwrap_or(String::from("Invalid Owner Address"));
+    let metadata = token_metadata(&mint);
+
+    // Only the owner can sign for the account, frozen accounts cannot send
+    let can_send = state != "FROZEN"
+        && !amount.is_zero()
+        && ACTIVE_CONNECTION
+            .read()
+            .connected_account()
+            .is_ok_and(|wallet_account| wallet_account.address() == owner);
 
     rsx! {
         div { class: "flex flex-col items-start p-4 w-[250px] m-5 rounded-lg bg-true-blue",
@@ -270,11 +404,23 @@ pub fn TokenAccountCard(
                         }
                     }
 
+                    if let Some(metadata) = metadata {
+                        div { class: "text-black dark:text-white mt-2 w-full flex items-center",
+                            if let Some(image) = metadata.image {
+                                img { class: "w-[28px] h-[28px] mr-2 rounded-full", src: image, alt: metadata.symbol.clone() }
+                            }
+                            div {class:"flex flex-col",
+                                span {class:"text-lg", {metadata.name}}
+                                span {class:"text-xs opacity-80", {metadata.symbol}}
+                            }
+                        }
+                    }
+
                     div { class: "text-black text-lg dark:text-white mt-2 w-full flex items-start justify-between",
 
                         div {class:"flex items-center",
                             div{class:"w-1/5", {MintSvg()} }
-                            div{class:"w-4/5 flex text-sm pl-2", {link_target_blank(&format_address_url(&mint), &shortened_mint_address) } }
+                            div{class:"w-4/5 flex text-sm pl-2", {address_link(&mint, &shortened_mint_address) } }
                         }
                     }
 
@@ -282,7 +428,7 @@ pub fn TokenAccountCard(
 
                         div {class:"flex items-center",
                             div{class:"w-1/5", {AtaSvg()} }
-                            div{class:"w-4/5 flex text-sm pl-2", {link_target_blank(&format_address_url(&ata_address), &shortened_ata_address) } }
+                            div{class:"w-4/5 flex text-sm pl-2", {address_link(&ata_address, &shortened_ata_address) } }
                         }
                     }
 
@@ -292,9 +438,30 @@ pub fn TokenAccountCard(
                              div{class:"w-3/5 flex text-[12px] p-1", {token_balance} }
                         }
                     }
+
+                    div { class: "flex w-full flex-wrap gap-1 mt-2",
+                        div { class: "bg-green-100 text-green-800 text-xs font-semibold px-2.5 py-0.5 rounded-full",
+                            {program.to_string()}
+                        }
+                        for extension in extensions.iter().filter(|extension| extension.is_notable()) {
+                            div { class: "bg-blue-100 text-blue-800 text-xs font-semibold px-2.5 py-0.5 rounded-full dark:bg-blue-200 dark:text-blue-800",
+                                {extension.to_string()}
+                            }
+                        }
+                    }
+
+                    if can_send {
+                        button {
+                            onclick:move|_|{show_send_modal.set(true)},
+                            class:"flex items-center text-sm text-true-blue bg-white hover:bg-blue-100 px-4 py-1 mt-3 rounded-full",
+                            span{class:"w-[20px] flex mr-1", {SendSvg()}} "Send"
+                        }
+                    }
                 }
             }
         }
+
+        SendToken { show_send_modal, mint: mint.clone(), ata_address: ata_address.clone(), balance: amount, program }
     }
 }
 
@@ -305,6 +472,7 @@ fn TxCard(
     state: Option<String>,
     succeeded: bool,
     address: String,
+    memos: Vec<String>,
 ) -> Element {
     let cluster = CLUSTER_STORAGE.read().active_cluster().cluster();
     let cluster_image = get_cluster_svg(cluster);
@@ -346,7 +514,7 @@ fn TxCard(
                         div {class:"flex items-center",
                             span { class: "w-[20px]", {UserSvg()} }
                             span { class: "flex text-sm pl-2",
-                            {link_target_blank(&format_address_url(&address), &shortened_address)}
+                            {address_link(&address, &shortened_address)}
                             }
                         }
                     }
@@ -355,7 +523,9 @@ fn TxCard(
 
                         div {class:"flex items-center",
                             span { class: "w-[20px]", {SignatureSvg()} }
-                            span { class: "flex text-sm pl-2", {link_target_blank(&format_tx_url(&tx), &shortened_tx) } }
+                            span { class: "flex text-sm pl-2",
+                                Link { class: "underline", to: Route::TransactionDetail { signature: tx.clone() }, {shortened_tx} }
+                            }
                             div {class:"flex items-center",
                                 span { class: "ml-2 w-[15px]", {succeeded} }
                             }
@@ -370,20 +540,45 @@ fn TxCard(
                             }
                         }
                     }
+
+                    for memo in memos {
+                        div { class: "text-black dark:text-white mt-2 w-full flex items-start text-sm",
+                            span { class: "shrink-0 bg-blue-100 text-blue-800 text-xs font-semibold px-2.5 py-0.5 rounded-full mr-2", "MEMO" }
+                            span { class: "break-all", "{memo}" }
+                        }
+                    }
                 }
             }
         }
     }
 }
 
+/// Only this badge subscribes to [CLUSTER_SLOT] since
+/// slot notifications arrive several times a second
+#[component]
+fn LiveSlot() -> Element {
+    let Some(slot) = *CLUSTER_SLOT.read() else {
+        return rsx! {};
+    };
+
+    rsx! {
+        div { class:"flex w-full items-center justify-center mt-2",
+            span { class: "bg-blue-100 text-blue-800 text-xs font-semibold px-2.5 py-0.5 rounded-full dark:bg-blue-200 dark:text-blue-800",
+                "LIVE · SLOT {slot}"
+            }
+        }
+    }
+}
+
 pub async fn fetch_account_state(
     success_msg: Option<&str>,
     error_msg: Option<&str>,
     address: &str,
+    cancel_handle: &CancelHandle,
 ) {
     LOADING.write().replace(());
 
-    match crate::accounts_runner(address).await {
+    match crate::accounts_runner(address, cancel_handle).await {
         Ok(value) => {
             *ACCOUNT_STATE.write() = value;
             if let Some(success_msg) = success_msg {
@@ -392,14 +587,93 @@ pub async fn fetch_account_state(
                     .push_back(NotificationInfo::new(success_msg));
             }
         }
+        // The view was left, nobody is waiting for the result
+        Err(RpcError::Cancelled) => {}
         Err(error) => {
             if let Some(error_msg) = error_msg {
                 GLOBAL_MESSAGE
                     .write()
-                    .push_back(NotificationInfo::error(format!("{error_msg}: {:?}", error)));
+                    .push_back(NotificationInfo::error(format!("{error_msg}: {error}")));
             }
         }
     }
 
     LOADING.write().take();
 }
+
+/// Appends the page of signatures older than the loaded ones to [ACCOUNT_STATE]
+pub async fn fetch_more_transactions(address: &str, cancel_handle: &CancelHandle) {
+    let Some(before) = ACCOUNT_STATE.peek().history_cursor.clone() else {
+        return;
+    };
+
+    let client = RpcClient::with_cancel_handle(cancel_handle.clone());
+
+    match load_transactions_page(&client, address, &before).await {
+        Ok(page) => {
+            // The state may have been reloaded while the page was loading
+            if ACCOUNT_STATE.peek().history_cursor.as_ref() == Some(&before) {
+                ACCOUNT_STATE.write().add_transactions_page(page);
+            }
+        }
+        Err(RpcError::Cancelled) => {}
+        Err(error) => {
+            GLOBAL_MESSAGE
+                .write()
+                .push_back(NotificationInfo::error(format!("LOAD MORE ERROR: {error}")));
+        }
+    }
+}
+
+/// Opens the PubSub connection of the active cluster, replacing the previous
+/// one, and keeps [ACCOUNT_STATE] of `address` up to date from its notifications
+fn connect_pubsub(ws_endpoint: &str, address: &str) {
+    let Ok(runtime) = Runtime::current() else {
+        return;
+    };
+
+    let owner = address.to_string();
+
+    // Notifications arrive from WebSocket callbacks which
+    // run outside of Dioxus so enter its runtime first
+    let pubsub = PubSubClient::connect(ws_endpoint, move |notification| {
+        let owner = owner.clone();
+
+        runtime.on_scope(ScopeId::ROOT, || match notification {
+            PubSubNotification::Account { lamports, .. } => {
+                ACCOUNT_STATE.write().balance = Lamports::new(lamports);
+            }
+            PubSubNotification::Signature { signature, err, .. } => {
+                let shortened_signature = wallet_adapter::Utils::shorten_base58(&signature)
+                    .map(|signature| signature.to_string())
+                    .unwrap_or(signature);
+
+                let message = match err {
+                    None => NotificationInfo::new(format!("CONFIRMED: {shortened_signature}")),
+                    Some(error) => {
+                        NotificationInfo::error(format!("FAILED: {shortened_signature} - {error}"))
+                    }
+                };
+                GLOBAL_MESSAGE.write().push_back(message);
+
+                // Keeps the pages of older transactions already loaded
+                spawn(async move {
+                    if let Ok(latest) = load_account_state(&RpcClient::new(), &owner).await {
+                        ACCOUNT_STATE.write().merge_latest(latest);
+                    }
+                });
+            }
+            PubSubNotification::Slot { slot, .. } => {
+                CLUSTER_SLOT.write().replace(slot);
+            }
+        });
+    });
+
+    pubsub.subscribe(Subscription::Account(address.to_string()));
+    pubsub.subscribe(Subscription::Slot);
+
+    CLUSTER_SLOT.write().take();
+    if let Some(previous) = PUBSUB.write().replace(pubsub) {
+        previous.close();
+    }
+}
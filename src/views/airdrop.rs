@@ -1,15 +1,14 @@
 use dioxus::prelude::*;
-use solana_sdk::native_token::LAMPORTS_PER_SOL;
 
 use crate::{
-    fetch_parser::request_airdrop, AirdropSvg, Loader, NotificationInfo, ACTIVE_CONNECTION,
-    GLOBAL_MESSAGE,
+    fetch_parser::request_airdrop, track_transaction, AirdropSvg, Lamports, Loader,
+    NotificationInfo, ACTIVE_CONNECTION, GLOBAL_MESSAGE,
 };
 
 #[component]
 pub fn Airdrop(show_airdrop_modal: Signal<bool>) -> Element {
     let mut loading = use_signal(|| false);
-    let mut lamports = use_signal(|| 0u64);
+    let mut lamports = use_signal(|| Some(Lamports::from_sol(2)));
 
     let mut address = String::default();
 
@@ -49,34 +48,41 @@ pub fn Airdrop(show_airdrop_modal: Signal<bool>) -> Element {
                                 div { class: "shrink-0 select-none text-base text-gray-500 sm:text-sm/6","SOL" }
                                 input {
                                     oninput: move |event| {
-                                        let data = event.data.value().as_str().parse::<u64>().unwrap_or(1);
-                                        lamports.set(data * LAMPORTS_PER_SOL);
+                                        lamports.set(event.data.value().parse::<Lamports>().ok());
                                     },
                                     class: "focus:outline-none bg-transparent border-b-2 border-white block min-w-0 grow ml-2 text-black dark:text-white placeholder:text-gray-400 sm:text-sm/6",
                                     id: "airdrop",
-                                    min: "0",
                                     name: "airdrop",
                                     placeholder: "2",
-                                    r#type: "number",
+                                    inputmode: "decimal",
+                                    r#type: "text",
                                     value: "2",
                                 }
                             }
                         }
                         div { class: "flex-w-full items-center justify-center mt-4",
-                            button {disabled:*loading.read(),
+                            button {disabled:*loading.read() || lamports.read().is_none(),
                                 onclick:move|_|{
                                     let address = address.clone();
+                                    let Some(lamports) = *lamports.read() else {
+                                        return;
+                                    };
+
                                     spawn(async move {
                                         loading.set(true);
 
-                                        if request_airdrop(*lamports.read(), &address).await.is_err() {
-                                            GLOBAL_MESSAGE.write().push_back(
-                                                NotificationInfo::error("REQUEST AIRDROP ERROR: You might have reached your daily limit.")
-                                            );
-                                        }else {
-                                            GLOBAL_MESSAGE.write().push_back(
-                                                NotificationInfo::new("REQUESTED AIRDROP")
-                                            );
+                                        match request_airdrop(lamports, &address).await {
+                                            Ok(signature) => {
+                                                track_transaction(&signature, "Airdrop");
+                                                GLOBAL_MESSAGE.write().push_back(
+                                                    NotificationInfo::new("REQUESTED AIRDROP")
+                                                );
+                                            }
+                                            Err(error) => {
+                                                GLOBAL_MESSAGE.write().push_back(
+                                                    NotificationInfo::error(format!("REQUEST AIRDROP ERROR: {error}"))
+                                                );
+                                            }
                                         }
 
                                         show_airdrop_modal.set(false);
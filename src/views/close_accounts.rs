@@ -0,0 +1,108 @@
+use dioxus::prelude::*;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    close_account_batches, empty_token_accounts, prepare_close_accounts, reclaimable_rent,
+    shortened_address_link, AtaSvg, Loader, NotificationInfo, ACCOUNT_STATE, GLOBAL_MESSAGE,
+    TRANSACTION_REVIEW,
+};
+
+/// Closes the empty token accounts of [ACCOUNT_STATE] to get their rent back
+#[component]
+pub fn CloseEmptyAccounts(show_close_modal: Signal<bool>, public_key_bytes: [u8; 32]) -> Element {
+    let mut loading = use_signal(|| false);
+
+    if *show_close_modal.read() {
+        let accounts = empty_token_accounts(ACCOUNT_STATE.read().token_accounts());
+        let rent = reclaimable_rent(&accounts);
+        let transaction_count =
+            close_account_batches(&accounts, Pubkey::new_from_array(public_key_bytes)).len();
+
+        rsx! {
+            div { class: "fixed z-10 top-0 left-0 flex flex-col w-full h-full bg-[rgba(0,0,0,0.6)] justify-center items-center text-black dark:text-white",
+                div { class: "flex flex-col w-[90%] sm:w-[80%] md:w-[70%] min-h-64 max-h-[60%] lg:w-[90%] max-w-screen-sm justify-start items-center bg-white dark:bg-[#0b0414] rounded-3xl",
+                    div { class: "flex w-full justify-end items-center p-5",
+                        button {
+                            onclick:move|_|{show_close_modal.set(false)},
+                            class: "wallet-adapter-modal-button-close w-[30px] items-center justify-center",
+                            svg {
+                                fill: "none",
+                                view_box: "0 0 24 24",
+                                xmlns: "http://www.w3.org/2000/svg",
+                                path {
+                                    d: "m15 9.00004-6 5.99996m6 0-6-5.99996m3 11.99996c4.9706 0 9-4.0294 9-9 0-4.97056-4.0294-9-9-9-4.97056 0-9 4.02944-9 9 0 4.9706 4.02944 9 9 9z",
+                                    stroke: "#a6c1ee",
+                                    stroke_linecap: "round",
+                                    stroke_linejoin: "round",
+                                    stroke_width: "2",
+                                }
+                            }
+                        }
+                    }
+                    div { class: "overflow-y-scroll max-h-[90%] w-full mb-5 items-center justify-center flex flex-col",
+                        div { class: "flex text-true-blue dark:text-white w-full items-center justify-center",
+                            span{class:"w-[30px] flex mb-10 mr-2",{AtaSvg()}}
+                            span{class:"flex mb-10 mr-2 text-3xl","Clean Up"}
+                        }
+                        if accounts.is_empty() {
+                            p { class: "text-sm mb-10", "No empty token accounts left" }
+                        } else {
+                            div { class: "flex flex-col w-4/5 text-sm gap-1",
+                                p { "{accounts.len()} empty token accounts hold {rent} SOL of rent." }
+                                p { class: "opacity-80",
+                                    if transaction_count == 1 {
+                                        "They are closed in 1 transaction"
+                                    } else {
+                                        "They are closed in {transaction_count} transactions, each one is reviewed and signed on its own"
+                                    }
+                                }
+                                div { class: "flex flex-col w-full mt-2",
+                                    for account in accounts.iter() {
+                                        div { class: "flex w-full justify-between border-b border-gray-500 py-1",
+                                            span { {shortened_address_link(&account.address.to_string())} }
+                                            span { "{account.rent} SOL" }
+                                        }
+                                    }
+                                }
+                            }
+                            div { class: "flex w-full items-center justify-center mt-4",
+                                button {disabled:*loading.read(),
+                                    onclick:move|_|{
+                                        let accounts = accounts.clone();
+
+                                        spawn(async move {
+                                            loading.set(true);
+
+                                            match prepare_close_accounts(&accounts, public_key_bytes).await {
+                                                Ok(prepared) => {
+                                                    TRANSACTION_REVIEW.write().extend(prepared);
+                                                }
+                                                Err(error) => {
+                                                    GLOBAL_MESSAGE.write().push_back(
+                                                        NotificationInfo::error(format!("CLEAN UP ERROR: {:?}", error))
+                                                    );
+                                                }
+                                            }
+
+                                            loading.set(false);
+                                            show_close_modal.set(false);
+                                        });
+                                    },
+                                    class:if *loading.read() {""}else{"bg-true-blue hover:bg-cobalt-blue"},
+                                    class:"flex text-sm mb-10  text-white text-black px-4 py-1 items-center justify-center rounded-full",
+                                    if *loading.read() {
+                                        {Loader()} span {class:"text-true-blue", "Preparing transactions..."}
+                                    }else {
+                                        "RECLAIM {rent} SOL"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    } else {
+        rsx! {}
+    }
+}
@@ -2,9 +2,11 @@ use dioxus::prelude::*;
 use wallet_adapter::Cluster;
 
 use crate::{
+    mask_endpoint, now_ms,
     utils::{get_cluster_svg, trunk_cluster_name},
-    AdapterCluster, BinSvg, CheckSvg, CloseSvg, ClusterName, ClustersSvg, LinkSvg,
-    NotificationInfo, CLUSTER_STORAGE, GLOBAL_MESSAGE,
+    AdapterCluster, BinSvg, CheckSvg, CloseSvg, ClusterHeader, ClusterName, ClustersSvg,
+    EndpointHealth, LinkSvg, NotificationInfo, PriorityFeePicker, CLUSTER_STORAGE, ENDPOINT_HEALTH,
+    GLOBAL_MESSAGE,
 };
 
 #[component]
@@ -57,9 +59,16 @@ fn ClusterInfo() -> Element {
                         div { class: "bg-blue-100 text-blue-800 text-sm font-semibold px-2.5 py-0.5 rounded-full dark:bg-blue-200 dark:text-blue-800",
                             {adapter_cluster.cluster().chain()}
                         }
-                        div {class:"text-sm mt-2",
-                            {adapter_cluster.endpoint()}
+                        EndpointList { adapter_cluster: adapter_cluster.clone() }
+                        div {class:"text-xs mt-1 opacity-80 break-all",
+                            {mask_endpoint(&adapter_cluster.ws_endpoint())}
                         }
+                        for header in adapter_cluster.headers() {
+                            div {class:"text-xs mt-1 opacity-80 break-all",
+                                {header.name()} ": " {header.display_value()}
+                            }
+                        }
+                        PriorityFeePicker { cluster_name: adapter_cluster.name().to_string() }
                     }
 
                     div { class: "flex w-full items-center justify-between",
@@ -83,6 +92,49 @@ fn ClusterInfo() -> Element {
     }
 }
 
+#[component]
+fn EndpointList(adapter_cluster: AdapterCluster) -> Element {
+    let serving_endpoint = ENDPOINT_HEALTH
+        .read()
+        .serving_endpoint(&adapter_cluster, now_ms());
+
+    rsx! {
+        for endpoint in adapter_cluster.endpoints() {
+            div {class:"flex w-full flex-col text-sm mt-2",
+                div {class:"flex w-full items-center break-all",
+                    {mask_endpoint(endpoint)}
+                    if serving_endpoint.as_ref() == Some(endpoint) {
+                        span { class: "ml-2 bg-green-100 text-green-800 text-xs font-semibold px-2 py-0.5 rounded-full",
+                            "SERVING"
+                        }
+                    }
+                }
+                {EndpointHealthInfo(ENDPOINT_HEALTH.read().get(endpoint))}
+            }
+        }
+    }
+}
+
+fn EndpointHealthInfo(health: EndpointHealth) -> Element {
+    if health.requests() == 0 {
+        return rsx! {
+            div {class:"text-xs opacity-80", "No requests yet"}
+        };
+    }
+
+    let latency = health
+        .latency_ms()
+        .map(|latency_ms| latency_ms.to_string() + " ms")
+        .unwrap_or(String::from("-"));
+
+    rsx! {
+        div {class:"flex w-full justify-between text-xs opacity-80",
+            span { "Latency: " {latency} }
+            span { "Errors: " {health.error_rate().to_string()} "%" }
+        }
+    }
+}
+
 fn Switch(cluster_name: &str) -> Element {
     let cluster_name = cluster_name.to_string();
 
@@ -132,6 +184,9 @@ fn AddClusterModal(mut show_add_cluster_modal: Signal<bool>) -> Element {
     struct AddCluster {
         name: String,
         endpoint: String,
+        fallback_endpoints: Vec<String>,
+        ws_endpoint: String,
+        headers: Vec<ClusterHeader>,
         network: Cluster,
     }
 
@@ -144,7 +199,7 @@ fn AddClusterModal(mut show_add_cluster_modal: Signal<bool>) -> Element {
         rsx! {
             div {
                 class: "fixed z-10 flex flex-col w-full h-full bg-[rgba(0,0,0,0.6)] justify-center items-center",
-                div { class: "flex flex-col w-[90%] sm:w-[80%] md:w-[70%] min-h-64 max-h-[60%] lg:w-[90%] max-w-screen-sm justify-start items-center bg-gray-200 dark:bg-[#10141f] rounded-3xl",
+                div { class: "flex flex-col w-[90%] sm:w-[80%] md:w-[70%] min-h-64 max-h-[60%] overflow-y-auto lg:w-[90%] max-w-screen-sm justify-start items-center bg-gray-200 dark:bg-[#10141f] rounded-3xl",
                     div { class: "flex w-full justify-end items-center p-5",
                         button {
                             onclick: move |_| {
@@ -199,6 +254,79 @@ fn AddClusterModal(mut show_add_cluster_modal: Signal<bool>) -> Element {
                                 required: true,
                             }
                         }
+                        label {
+                            class: "block mb-2 text-sm mt-5 font-medium text-gray-900 dark:text-white",
+                            r#for: "fallback-endpoints",
+                            "Fallback URLs (optional, comma separated)"
+                        }
+                        div { class: "flex w-full",
+                            span { class: "w-[40px] inline-flex items-center px-3 text-lg text-gray-900 bg-gray-200 border rounded-e-0 border-gray-300 border-e-0 rounded-s-md dark:bg-gray-600 dark:text-gray-400 dark:border-gray-600",
+                                {LinkSvg()}
+                            }
+                            input {
+                                oninput: move |event| {
+                                    let fallback_endpoints = event
+                                        .data
+                                        .value()
+                                        .split(',')
+                                        .map(|endpoint| endpoint.trim().to_string())
+                                        .filter(|endpoint| validate_url(endpoint))
+                                        .collect::<Vec<String>>();
+                                    add_cluster.write().fallback_endpoints = fallback_endpoints;
+                                },
+                                class: "rounded-none rounded-e-lg bg-gray-50 border text-gray-900 focus:ring-blue-500 focus:border-blue-500 block flex-1 min-w-0 w-full text-sm border-gray-300 p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500",
+                                id: "fallback-endpoints",
+                                placeholder: "eg. http://localhost:8898, https://my-rpc.example",
+                                r#type: "text",
+                            }
+                        }
+                        label {
+                            class: "block mb-2 text-sm mt-5 font-medium text-gray-900 dark:text-white",
+                            r#for: "ws-endpoint",
+                            "WebSocket URL (optional)"
+                        }
+                        div { class: "flex w-full",
+                            span { class: "w-[40px] inline-flex items-center px-3 text-lg text-gray-900 bg-gray-200 border rounded-e-0 border-gray-300 border-e-0 rounded-s-md dark:bg-gray-600 dark:text-gray-400 dark:border-gray-600",
+                                {LinkSvg()}
+                            }
+                            input {
+                                oninput: move |event| {
+                                    let data = event.data.value();
+                                    if data.is_empty() || validate_ws_url(&data) {
+                                        add_cluster.write().ws_endpoint = data;
+                                    }
+                                },
+                                class: "rounded-none rounded-e-lg bg-gray-50 border text-gray-900 focus:ring-blue-500 focus:border-blue-500 block flex-1 min-w-0 w-full text-sm border-gray-300 p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500",
+                                id: "ws-endpoint",
+                                placeholder: "Defaults to the URL Endpoint ,eg. ws://localhost:8900",
+                                r#type: "url",
+                            }
+                        }
+                        label {
+                            class: "block mb-2 text-sm mt-5 font-medium text-gray-900 dark:text-white",
+                            r#for: "headers",
+                            "Request headers (optional, one `Name: value` per line)"
+                        }
+                        div { class: "flex w-full",
+                            span { class: "w-[40px] inline-flex items-center px-3 text-lg text-gray-900 bg-gray-200 border rounded-e-0 border-gray-300 border-e-0 rounded-s-md dark:bg-gray-600 dark:text-gray-400 dark:border-gray-600",
+                                {LinkSvg()}
+                            }
+                            textarea {
+                                oninput: move |event| {
+                                    let headers = event
+                                        .data
+                                        .value()
+                                        .lines()
+                                        .filter_map(ClusterHeader::parse)
+                                        .collect::<Vec<ClusterHeader>>();
+                                    add_cluster.write().headers = headers;
+                                },
+                                class: "rounded-none rounded-e-lg bg-gray-50 border text-gray-900 focus:ring-blue-500 focus:border-blue-500 block flex-1 min-w-0 w-full text-sm border-gray-300 p-2.5 dark:bg-gray-700 dark:border-gray-600 dark:placeholder-gray-400 dark:text-white dark:focus:ring-blue-500 dark:focus:border-blue-500",
+                                id: "headers",
+                                placeholder: "eg. Authorization: Bearer <API key>",
+                                rows: "2",
+                            }
+                        }
                         label {
                             class: "block mb-2 text-sm mt-5 font-medium text-gray-900 dark:text-white",
                             r#for: "network",
@@ -232,11 +360,23 @@ fn AddClusterModal(mut show_add_cluster_modal: Signal<bool>) -> Element {
                             if should_show_button {
                                 button {
                                     onclick: move |_| {
-                                        let adapter_cluster = AdapterCluster::new()
+                                        let mut adapter_cluster = AdapterCluster::new()
                                             .add_name(add_cluster.read().name.as_str())
                                             .add_endpoint(add_cluster.read().endpoint.clone().as_str())
                                             .add_cluster(add_cluster.read().network);
 
+                                        for endpoint in add_cluster.read().fallback_endpoints.iter() {
+                                            adapter_cluster = adapter_cluster.add_fallback_endpoint(endpoint);
+                                        }
+
+                                        for header in add_cluster.read().headers.iter() {
+                                            adapter_cluster = adapter_cluster.add_header(header.clone());
+                                        }
+
+                                        if !add_cluster.read().ws_endpoint.is_empty() {
+                                            adapter_cluster = adapter_cluster.add_ws_endpoint(add_cluster.read().ws_endpoint.as_str());
+                                        }
+
                                         let name = adapter_cluster.name().to_string();
 
                                         if let Err(error) = CLUSTER_STORAGE.write().add_cluster(adapter_cluster){
@@ -271,3 +411,9 @@ fn validate_url(value: &str) -> bool {
 
     scheme_exists && value.len() > 8
 }
+
+fn validate_ws_url(value: &str) -> bool {
+    let scheme_exists = value.starts_with("ws://") || value.starts_with("wss://");
+
+    scheme_exists && value.len() > 6
+}
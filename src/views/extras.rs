@@ -1,7 +1,7 @@
 use dioxus::prelude::*;
 
 use crate::{
-    views::{SignMessage, SignTx},
+    views::{ComposeTx, DurableNonce, SignMessage},
     ConnectWalletFirst, SignInWithSolana, ACTIVE_CONNECTION,
 };
 
@@ -12,7 +12,8 @@ pub fn Extras() -> Element {
             div { class:"flex justify-center mt-10 mb-5 gap-8 w-full flex-wrap items-stretch",
                 SignInWithSolana{}
                 SignMessage{}
-                SignTx{}
+                DurableNonce{}
+                ComposeTx{}
             }
         }
     } else {
@@ -0,0 +1,354 @@
+use std::str::FromStr;
+
+use dioxus::prelude::*;
+use solana_sdk::{message::AddressLookupTableAccount, packet::PACKET_DATA_SIZE, pubkey::Pubkey};
+use wallet_adapter::{WalletError, WalletResult};
+
+use crate::{
+    prepare_composed_transaction, seeded_account_address, ComposedInstruction, Loader,
+    NotificationInfo, RawAccountMeta, RpcClient, SignTxSvg, TransactionComposer, ACCOUNT_STATE,
+    ACTIVE_CONNECTION, DURABLE_NONCE, GLOBAL_MESSAGE, TRANSACTION_REVIEW,
+};
+
+/// Builds a transaction out of several instructions, then hands it to the
+/// review to be signed only or signed and sent
+#[component]
+pub fn ComposeTx() -> Element {
+    let mut composer = use_signal(TransactionComposer::default);
+    let mut loading = use_signal(|| false);
+    // Fetched when the lookup table address changes, the size is computed against it
+    let mut lookup_tables =
+        use_signal(|| WalletResult::Ok(Vec::<AddressLookupTableAccount>::new()));
+
+    let mut public_key = [0u8; 32];
+
+    if let Ok(wallet_account) = ACTIVE_CONNECTION.read().connected_account() {
+        public_key = wallet_account.public_key();
+    }
+
+    let payer = Pubkey::new_from_array(public_key);
+    let size = lookup_tables
+        .read()
+        .as_ref()
+        .map_err(Clone::clone)
+        .and_then(|lookup_tables| {
+            composer
+                .read()
+                .serialized_size(&payer, lookup_tables, DURABLE_NONCE.read().as_ref())
+        });
+    let fits = size.as_ref().is_ok_and(|size| *size <= PACKET_DATA_SIZE);
+
+    let submit = move |send: bool| {
+        spawn(async move {
+            loading.set(true);
+
+            match prepare_composed_transaction(&composer.read(), send, public_key).await {
+                Ok(prepared) => TRANSACTION_REVIEW.write().push_back(prepared),
+                Err(error) => GLOBAL_MESSAGE
+                    .write()
+                    .push_back(NotificationInfo::error(format!(
+                        "COMPOSE TX ERROR: {error:?}"
+                    ))),
+            }
+
+            loading.set(false);
+        });
+    };
+
+    rsx! {
+        div { class:"flex dark:bg-[#160231] bg-white flex-col w-full max-w-screen-md p-5 rounded-lg dark:shadow-2xl shadow-sm border dark:border-none",
+            div {class:"w-full flex flex-col items-center text-center text-true-blue justify-center mb-5",
+                div{class:"w-[80px] flex flex-col", {SignTxSvg()}}
+                div{class:"w-full text-sm", "Transaction Composer"}
+            }
+            div { class: "flex w-full flex-wrap items-center justify-center gap-1 mb-5",
+                for kind in ComposedInstruction::KINDS {
+                    button {
+                        onclick: {
+                            let kind = kind.clone();
+                            move |_| { composer.write().add(kind.clone()); }
+                        },
+                        class: "text-xs px-3 py-1 rounded-full border border-true-blue text-true-blue dark:text-white hover:bg-cobalt-blue hover:text-white",
+                        "+ " {kind.label()}
+                    }
+                }
+            }
+            if composer.read().instructions.is_empty() {
+                p { class: "text-sm text-center opacity-80", "Add the instructions of the transaction, they run from top to bottom" }
+            }
+            div { class: "flex flex-col w-full gap-3",
+                for (index, instruction) in composer.read().instructions.iter().cloned().enumerate() {
+                    div { class: "flex flex-col w-full text-sm border border-gray-500 rounded-lg p-3",
+                        div { class: "flex w-full justify-between items-center mb-2",
+                            span { class: "text-true-blue dark:text-white", "{index + 1}. " {instruction.label()} }
+                            div { class: "flex gap-1",
+                                button { class: "text-xs px-2 rounded-full border border-true-blue", onclick: move |_| { composer.write().move_up(index); }, "Up" }
+                                button { class: "text-xs px-2 rounded-full border border-true-blue", onclick: move |_| { composer.write().move_down(index); }, "Down" }
+                                button { class: "text-xs px-2 rounded-full border border-red-500 text-red-500", onclick: move |_| { composer.write().remove(index); }, "Remove" }
+                            }
+                        }
+                        InstructionFields { composer, index, instruction, payer: public_key }
+                    }
+                }
+            }
+            div { class: "flex w-full items-center gap-2 text-sm mt-5",
+                span { class: "w-24 shrink-0 opacity-80", "Lookup table" }
+                input {
+                    class: "w-full focus:outline-none bg-transparent border-b-2 border-true-blue text-black dark:text-white placeholder:text-gray-400",
+                    r#type: "text",
+                    value: composer.read().lookup_table.clone(),
+                    placeholder: "Optional address lookup table",
+                    onchange: move |event: Event<FormData>| {
+                        composer.write().lookup_table = event.value();
+
+                        spawn(async move {
+                            let composer = composer.peek().clone();
+                            lookup_tables.set(composer.lookup_tables(&RpcClient::new()).await);
+                        });
+                    },
+                }
+            }
+            div { class: "flex w-full justify-center text-sm mt-5",
+                match size {
+                    Ok(size) => rsx! {
+                        span { class: if fits { "" } else { "text-red-500" },
+                            "{size} / {PACKET_DATA_SIZE} bytes"
+                        }
+                    },
+                    Err(error) => rsx! {
+                        span { class: "text-red-500 text-xs text-center break-all", {error_message(&error)} }
+                    },
+                }
+            }
+            div { class: "flex items-center justify-center gap-4 mt-3",
+                if *loading.read() {
+                    {Loader()} span { class: "text-true-blue text-sm", "Preparing transaction..." }
+                } else {
+                    button {
+                        disabled: !fits,
+                        onclick: move |_| submit(false),
+                        class: "text-sm px-5 py-2 rounded-full border border-true-blue text-true-blue dark:text-white",
+                        "SIGN ONLY"
+                    }
+                    button {
+                        disabled: !fits,
+                        onclick: move |_| submit(true),
+                        class: if fits { "bg-true-blue hover:bg-cobalt-blue" } else { "bg-gray-400" },
+                        class: "text-sm text-white px-5 py-2 rounded-full",
+                        "SIGN AND SEND"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The inputs of the instruction at `index`, each edit is written back to `composer`
+#[component]
+fn InstructionFields(
+    composer: Signal<TransactionComposer>,
+    index: usize,
+    instruction: ComposedInstruction,
+    payer: [u8; 32],
+) -> Element {
+    // Replaces the instruction at `index` with the edited copy
+    let mut edit = move |update: &dyn Fn(&mut ComposedInstruction)| {
+        if let Some(instruction) = composer.write().instructions.get_mut(index) {
+            update(instruction);
+        }
+    };
+
+    match instruction {
+        ComposedInstruction::SolTransfer { recipient, amount } => rsx! {
+            Field { label: "Recipient", value: recipient, placeholder: "Recipient Address",
+                oninput: move |value: String| edit(&|instruction| if let ComposedInstruction::SolTransfer { recipient, .. } = instruction { *recipient = value.clone() }),
+            }
+            Field { label: "Amount", value: amount, placeholder: "SOL",
+                oninput: move |value: String| edit(&|instruction| if let ComposedInstruction::SolTransfer { amount, .. } = instruction { *amount = value.clone() }),
+            }
+        },
+        ComposedInstruction::TokenTransfer {
+            source,
+            recipient,
+            amount,
+            ..
+        } => rsx! {
+            div { class: "flex w-full items-center gap-2 mb-1",
+                span { class: "w-24 shrink-0 opacity-80", "Token" }
+                select {
+                    class: "w-full bg-transparent border-b-2 border-true-blue focus:outline-none",
+                    onchange: move |event: Event<FormData>| {
+                        let selected = event.value();
+                        let token_account = ACCOUNT_STATE
+                            .read()
+                            .token_accounts()
+                            .iter()
+                            .find(|token_account| token_account.ata_address() == selected)
+                            .cloned();
+
+                        edit(&|instruction| {
+                            if let (Some(token_account), ComposedInstruction::TokenTransfer { source, mint, decimals, program, .. }) = (token_account.as_ref(), instruction) {
+                                *source = token_account.ata_address();
+                                *mint = token_account.mint();
+                                *decimals = token_account.amount().decimals();
+                                *program = token_account.program();
+                            }
+                        });
+                    },
+                    option { value: "", selected: source.is_empty(), "Pick a token account" }
+                    for token_account in ACCOUNT_STATE.read().token_accounts() {
+                        option {
+                            value: token_account.ata_address(),
+                            selected: token_account.ata_address() == source,
+                            {shorten(&token_account.mint())} " (" {token_account.balance()} ")"
+                        }
+                    }
+                }
+            }
+            if ACCOUNT_STATE.read().token_accounts_is_empty() {
+                p { class: "text-xs opacity-80 mb-1", "Open Accounts to load your token accounts" }
+            }
+            Field { label: "Recipient", value: recipient, placeholder: "Recipient Address",
+                oninput: move |value: String| edit(&|instruction| if let ComposedInstruction::TokenTransfer { recipient, .. } = instruction { *recipient = value.clone() }),
+            }
+            Field { label: "Amount", value: amount, placeholder: "Token amount",
+                oninput: move |value: String| edit(&|instruction| if let ComposedInstruction::TokenTransfer { amount, .. } = instruction { *amount = value.clone() }),
+            }
+        },
+        ComposedInstruction::Memo { memo } => rsx! {
+            Field { label: "Memo", value: memo, placeholder: "Written to the transaction logs",
+                oninput: move |value: String| edit(&|instruction| if let ComposedInstruction::Memo { memo } = instruction { *memo = value.clone() }),
+            }
+        },
+        ComposedInstruction::CreateAccount {
+            seed,
+            lamports,
+            space,
+            owner,
+        } => {
+            let address = Pubkey::from_str(owner.trim()).ok().and_then(|owner| {
+                seeded_account_address(&Pubkey::new_from_array(payer), &seed, &owner).ok()
+            });
+
+            rsx! {
+                Field { label: "Seed", value: seed, placeholder: "Up to 32 characters",
+                    oninput: move |value: String| edit(&|instruction| if let ComposedInstruction::CreateAccount { seed, .. } = instruction { *seed = value.clone() }),
+                }
+                Field { label: "Balance", value: lamports, placeholder: "SOL",
+                    oninput: move |value: String| edit(&|instruction| if let ComposedInstruction::CreateAccount { lamports, .. } = instruction { *lamports = value.clone() }),
+                }
+                Field { label: "Space", value: space, placeholder: "Bytes",
+                    oninput: move |value: String| edit(&|instruction| if let ComposedInstruction::CreateAccount { space, .. } = instruction { *space = value.clone() }),
+                }
+                Field { label: "Owner", value: owner, placeholder: "Owner Program Id",
+                    oninput: move |value: String| edit(&|instruction| if let ComposedInstruction::CreateAccount { owner, .. } = instruction { *owner = value.clone() }),
+                }
+                if let Some(address) = address {
+                    p { class: "text-xs opacity-80 break-all", "Creates {address}" }
+                }
+            }
+        }
+        ComposedInstruction::Raw {
+            program_id,
+            accounts,
+            data,
+        } => rsx! {
+            Field { label: "Program", value: program_id, placeholder: "Program Id",
+                oninput: move |value: String| edit(&|instruction| if let ComposedInstruction::Raw { program_id, .. } = instruction { *program_id = value.clone() }),
+            }
+            for (account_index, account) in accounts.into_iter().enumerate() {
+                div { class: "flex w-full items-center gap-2 mb-1",
+                    input {
+                        class: "w-full text-xs focus:outline-none bg-transparent border-b-2 border-true-blue placeholder:text-gray-400",
+                        r#type: "text",
+                        value: account.pubkey,
+                        placeholder: "Account {account_index + 1}",
+                        oninput: move |event: Event<FormData>| {
+                            let value = event.value();
+                            edit(&|instruction| if let Some(account) = raw_account(instruction, account_index) { account.pubkey = value.clone() });
+                        },
+                    }
+                    label { class: "flex items-center gap-1 text-xs",
+                        input {
+                            r#type: "checkbox",
+                            checked: account.is_signer,
+                            onchange: move |event: Event<FormData>| {
+                                let checked = event.checked();
+                                edit(&|instruction| if let Some(account) = raw_account(instruction, account_index) { account.is_signer = checked });
+                            },
+                        }
+                        "Signer"
+                    }
+                    label { class: "flex items-center gap-1 text-xs",
+                        input {
+                            r#type: "checkbox",
+                            checked: account.is_writable,
+                            onchange: move |event: Event<FormData>| {
+                                let checked = event.checked();
+                                edit(&|instruction| if let Some(account) = raw_account(instruction, account_index) { account.is_writable = checked });
+                            },
+                        }
+                        "Writable"
+                    }
+                    button {
+                        class: "text-xs px-2 rounded-full border border-red-500 text-red-500",
+                        onclick: move |_| edit(&|instruction| if let ComposedInstruction::Raw { accounts, .. } = instruction { accounts.remove(account_index); }),
+                        "x"
+                    }
+                }
+            }
+            div { class: "flex w-full mb-1",
+                button {
+                    class: "text-xs px-2 rounded-full border border-true-blue",
+                    onclick: move |_| edit(&|instruction| if let ComposedInstruction::Raw { accounts, .. } = instruction { accounts.push(RawAccountMeta::default()) }),
+                    "+ Account"
+                }
+            }
+            Field { label: "Data", value: data, placeholder: "Hex, eg. 0x02000000",
+                oninput: move |value: String| edit(&|instruction| if let ComposedInstruction::Raw { data, .. } = instruction { *data = value.clone() }),
+            }
+        },
+    }
+}
+
+#[component]
+fn Field(
+    label: String,
+    value: String,
+    placeholder: String,
+    oninput: EventHandler<String>,
+) -> Element {
+    rsx! {
+        div { class: "flex w-full items-center gap-2 mb-1",
+            span { class: "w-24 shrink-0 opacity-80", "{label}" }
+            input {
+                class: "w-full focus:outline-none bg-transparent border-b-2 border-true-blue text-black dark:text-white placeholder:text-gray-400",
+                r#type: "text",
+                value,
+                placeholder,
+                oninput: move |event: Event<FormData>| oninput.call(event.value()),
+            }
+        }
+    }
+}
+
+fn raw_account(instruction: &mut ComposedInstruction, index: usize) -> Option<&mut RawAccountMeta> {
+    match instruction {
+        ComposedInstruction::Raw { accounts, .. } => accounts.get_mut(index),
+        _ => None,
+    }
+}
+
+/// The message of the parse errors shown under the composer
+fn error_message(error: &WalletError) -> String {
+    match error {
+        WalletError::Op(message) => message.clone(),
+        error => format!("{error:?}"),
+    }
+}
+
+fn shorten(value: &str) -> String {
+    wallet_adapter::Utils::shorten_base58(value)
+        .map(|value| value.to_string())
+        .unwrap_or(value.to_string())
+}
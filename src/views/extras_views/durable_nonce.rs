@@ -0,0 +1,169 @@
+use dioxus::prelude::*;
+use solana_sdk::pubkey::Pubkey;
+use wallet_adapter::{Utils, WalletResult};
+
+use crate::{
+    load_nonce_accounts, prepare_advance_nonce, prepare_create_nonce_account,
+    prepare_withdraw_nonce, Loader, NonceAccount, NonceSlot, NotificationInfo, PreparedTransaction,
+    RpcClient, ACTIVE_CONNECTION, DURABLE_NONCE, GLOBAL_MESSAGE, TRANSACTION_REVIEW,
+};
+
+/// Creates, advances and withdraws the nonce accounts derived from the
+/// connected wallet and picks the one new transactions take their blockhash from
+#[component]
+pub fn DurableNonce() -> Element {
+    let mut slots = use_signal(|| Option::<WalletResult<Vec<NonceSlot>>>::None);
+    let mut refreshing = use_signal(|| false);
+
+    let mut public_key = [0u8; 32];
+
+    if let Ok(wallet_account) = ACTIVE_CONNECTION.read().connected_account() {
+        public_key = wallet_account.public_key();
+    }
+
+    let reload = move || {
+        spawn(async move {
+            refreshing.set(true);
+            let owner = Pubkey::new_from_array(public_key);
+            let loaded = load_nonce_accounts(&RpcClient::new(), &owner)
+                .await
+                .map_err(|error| error.into());
+            slots.set(Some(loaded));
+            refreshing.set(false);
+        });
+    };
+
+    // Reloads once the queued nonce transactions were approved or cancelled
+    use_effect(move || {
+        if TRANSACTION_REVIEW.read().is_empty() {
+            reload();
+        }
+    });
+
+    let body = match slots.read().as_ref() {
+        None => rsx! {
+            div { class: "flex items-center justify-center text-sm", span{class:"mr-2", {Loader()}} "Loading nonce accounts..." }
+        },
+        Some(Err(error)) => rsx! {
+            p { class: "text-sm text-red-500 break-all", "Failed to load the nonce accounts: {error}" }
+        },
+        Some(Ok(slots)) => {
+            let accounts = slots
+                .iter()
+                .filter_map(|slot| slot.account)
+                .collect::<Vec<NonceAccount>>();
+            let empty_slot = slots.iter().find(|slot| slot.account.is_none()).cloned();
+
+            rsx! {
+                if accounts.is_empty() {
+                    p { class: "text-sm text-center", "No nonce accounts yet" }
+                }
+                for nonce_account in accounts {
+                    NonceAccountCard { nonce_account, public_key }
+                }
+                if let Some(slot) = empty_slot {
+                    div { class: "flex items-center justify-center",
+                        button {
+                            class: "bg-true-blue hover:bg-cobalt-blue mt-2 text-sm text-white px-5 py-2 rounded-full",
+                            onclick: move |_| {
+                                let slot = slot.clone();
+
+                                spawn(async move {
+                                    queue_nonce_transaction("CREATE NONCE ACCOUNT", prepare_create_nonce_account(&slot, public_key).await);
+                                });
+                            },
+                            "CREATE NONCE ACCOUNT"
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    rsx! {
+        div { class:"flex dark:bg-[#160231] bg-white flex-col w-[300px] p-5 rounded-lg dark:shadow-2xl shadow-sm border dark:border-none",
+            div {class:"w-full flex items-center justify-between text-true-blue dark:text-white mb-5",
+                div{class:"text-sm", "Durable Nonce"}
+                button {
+                    disabled: *refreshing.read(),
+                    onclick: move |_| reload(),
+                    class: "text-xs px-3 py-1 rounded-full border border-true-blue",
+                    if *refreshing.read() { {Loader()} } else { "Refresh" }
+                }
+            }
+            p { class: "text-xs opacity-80 mb-3",
+                "Transactions using a nonce account stay valid until the nonce is advanced instead of expiring with their blockhash"
+            }
+            div { class: "flex flex-col w-full gap-3", {body} }
+        }
+    }
+}
+
+#[component]
+fn NonceAccountCard(nonce_account: NonceAccount, public_key: [u8; 32]) -> Element {
+    let selected = *DURABLE_NONCE.read() == Some(nonce_account.address);
+    let is_authority = nonce_account.authority == Pubkey::new_from_array(public_key);
+
+    rsx! {
+        div { class: "flex flex-col w-full text-sm border-b border-gray-500 pb-3",
+            div { class: "flex w-full justify-between", span { "Address" } span { {shorten(&nonce_account.address.to_string())} } }
+            div { class: "flex w-full justify-between", span { "Authority" } span { {shorten(&nonce_account.authority.to_string())} } }
+            div { class: "flex w-full justify-between", span { "Nonce" } span { {shorten(&nonce_account.nonce.to_string())} } }
+            div { class: "flex w-full justify-between", span { "Balance" } span { "{nonce_account.lamports} SOL" } }
+            if is_authority {
+                div { class: "flex w-full flex-wrap gap-1 mt-2",
+                    button {
+                        onclick: move |_| {
+                            *DURABLE_NONCE.write() = if selected { None } else { Some(nonce_account.address) };
+                        },
+                        class: if selected { "bg-white text-true-blue border border-true-blue" } else { "border border-true-blue hover:bg-cobalt-blue hover:text-white" },
+                        class: "text-xs px-2 py-0.5 rounded-full",
+                        if selected { "Used for new transactions" } else { "Use for new transactions" }
+                    }
+                    button {
+                        onclick: move |_| {
+                            spawn(async move {
+                                queue_nonce_transaction("ADVANCE NONCE", prepare_advance_nonce(&nonce_account, public_key).await);
+                            });
+                        },
+                        class: "text-xs px-2 py-0.5 rounded-full border border-true-blue hover:bg-cobalt-blue hover:text-white",
+                        "Advance"
+                    }
+                    button {
+                        onclick: move |_| {
+                            // Withdrawing everything closes the account
+                            if selected {
+                                *DURABLE_NONCE.write() = None;
+                            }
+
+                            spawn(async move {
+                                queue_nonce_transaction("WITHDRAW NONCE", prepare_withdraw_nonce(&nonce_account, public_key).await);
+                            });
+                        },
+                        class: "text-xs px-2 py-0.5 rounded-full border border-true-blue hover:bg-cobalt-blue hover:text-white",
+                        "Withdraw"
+                    }
+                }
+            } else {
+                p { class: "text-xs opacity-80 mt-2", "Only its authority can use this nonce account" }
+            }
+        }
+    }
+}
+
+fn queue_nonce_transaction(action: &str, prepared: WalletResult<PreparedTransaction>) {
+    match prepared {
+        Ok(prepared) => TRANSACTION_REVIEW.write().push_back(prepared),
+        Err(error) => GLOBAL_MESSAGE
+            .write()
+            .push_back(NotificationInfo::error(format!(
+                "{action} ERROR: {error:?}"
+            ))),
+    }
+}
+
+fn shorten(value: &str) -> String {
+    Utils::shorten_base58(value)
+        .map(|value| value.to_string())
+        .unwrap_or(value.to_string())
+}
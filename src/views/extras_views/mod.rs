@@ -4,5 +4,8 @@ pub use siws::*;
 mod sign_message;
 pub use sign_message::*;
 
-mod sign_tx;
-pub use sign_tx::*;
+mod compose_tx;
+pub use compose_tx::*;
+
+mod durable_nonce;
+pub use durable_nonce::*;
@@ -13,12 +13,30 @@ pub use accounts::*;
 mod query_accounts;
 pub use query_accounts::*;
 
+mod transaction;
+pub use transaction::*;
+
+mod account;
+pub use account::*;
+
 mod airdrop;
 pub use airdrop::*;
 
 mod send_sol;
 pub use send_sol::*;
 
+mod send_token;
+pub use send_token::*;
+
+mod close_accounts;
+pub use close_accounts::*;
+
+mod review;
+pub use review::*;
+
+mod priority_fee;
+pub use priority_fee::*;
+
 mod receive_sol;
 pub use receive_sol::*;
 
@@ -0,0 +1,68 @@
+use dioxus::prelude::*;
+
+use crate::{PriorityFee, PRIORITY_FEES};
+
+/// Picks the [PriorityFee] of the transactions sent on `cluster_name`
+#[component]
+pub fn PriorityFeePicker(cluster_name: String) -> Element {
+    let priority_fee = PRIORITY_FEES.read().get(&cluster_name);
+    let mut custom_error = use_signal(|| Option::<String>::None);
+
+    let custom_price = match priority_fee {
+        PriorityFee::Custom(price) => price.to_string(),
+        _ => String::new(),
+    };
+
+    rsx! {
+        div { class: "flex flex-col w-full text-sm mt-2",
+            div { class: "text-xs opacity-80 mb-1", "Priority fee" }
+            div { class: "flex w-full flex-wrap gap-1",
+                for level in PriorityFee::LEVELS {
+                    button {
+                        onclick: {
+                            let cluster_name = cluster_name.clone();
+
+                            move |_| {
+                                custom_error.set(None);
+                                PRIORITY_FEES.write().set(&cluster_name, level);
+                            }
+                        },
+                        class: if priority_fee == level { "bg-white text-true-blue" } else { "border border-white hover:bg-cobalt-blue" },
+                        class: "text-xs px-2 py-0.5 rounded-full",
+                        {level.label()}
+                    }
+                }
+            }
+            div { class: "flex w-full items-center mt-1",
+                input {
+                    onchange: {
+                        let cluster_name = cluster_name.clone();
+
+                        move |event: Event<FormData>| {
+                            let value = event.data.value();
+
+                            match value.trim().parse::<u64>() {
+                                Ok(price) => {
+                                    custom_error.set(None);
+                                    PRIORITY_FEES.write().set(&cluster_name, PriorityFee::Custom(price));
+                                }
+                                Err(_) if value.trim().is_empty() => custom_error.set(None),
+                                Err(_) => custom_error.set(Some("Enter a whole number of micro-lamports".to_string())),
+                            }
+                        }
+                    },
+                    class: if matches!(priority_fee, PriorityFee::Custom(_)) { "border-white" } else { "border-transparent" },
+                    class: "w-full text-xs focus:outline-none bg-transparent border-b-2 placeholder:text-gray-300",
+                    name: "priority-fee-{cluster_name}",
+                    inputmode: "numeric",
+                    r#type: "text",
+                    value: custom_price,
+                    placeholder: "Custom micro-lamports per CU",
+                }
+            }
+            if let Some(error) = custom_error.read().as_ref() {
+                div { class: "text-xs text-red-200 mt-1", "{error}" }
+            }
+        }
+    }
+}
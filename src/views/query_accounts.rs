@@ -1,5 +1,5 @@
 use dioxus::prelude::*;
-use crate::AccountState;
+use crate::{AccountState, CancelHandle, RpcError};
 use crate::views::accounts::TokenAccountCard;
 
 // Dialog state: open/closed, input address, loading, and fetched result
@@ -12,6 +12,13 @@ pub fn QueryAccountDialog(show_query_dialog: Signal<bool>) -> Element {
     // Optional: Store the fetched account info
     let mut account_info = use_signal(|| None::<AccountState>);
 
+    // Closing the dialog or leaving the route aborts a query that is still running
+    let cancel_handle = use_hook(CancelHandle::new);
+    let close_cancel_handle = cancel_handle.clone();
+    let drop_cancel_handle = cancel_handle.clone();
+
+    use_drop(move || drop_cancel_handle.cancel());
+
     let on_query = move |_| {
         let address = input_address.read().trim().to_string();
         if address.is_empty() {
@@ -23,13 +30,17 @@ pub fn QueryAccountDialog(show_query_dialog: Signal<bool>) -> Element {
 
         // Spawn async fetching
         let address_clone = address.clone();
+        let cancel_handle = cancel_handle.clone();
         spawn(async move {
-            match crate::accounts_runner(&address_clone).await {
+            match crate::accounts_runner(&address_clone, &cancel_handle).await {
                 Ok(account_state) => {
+                    let token_accounts = account_state.token_accounts.clone();
                     account_info.set(Some(account_state));
+                    crate::resolve_token_metadata(token_accounts).await;
                 }
+                Err(RpcError::Cancelled) => {}
                 Err(e) => {
-                    error_message.set(Some(format!("Failed to fetch: {e:?}")));
+                    error_message.set(Some(format!("Failed to fetch: {e}")));
                 }
             }
             loading.set(false);
@@ -57,7 +68,10 @@ pub fn QueryAccountDialog(show_query_dialog: Signal<bool>) -> Element {
                     }
                     button {
                         class: "ml-2 text-gray-500 px-4 py-2",
-                        onclick: move |_| show_query_dialog.set(false),
+                        onclick: move |_| {
+                            close_cancel_handle.cancel();
+                            show_query_dialog.set(false);
+                        },
                         "Close"
                     }
                     if let Some(err) = error_message.read().as_ref() {
@@ -69,7 +83,7 @@ pub fn QueryAccountDialog(show_query_dialog: Signal<bool>) -> Element {
                     if let Some(account) = account_info.read().as_ref() {
                         // Render your account info here, or reuse your existing account view components
                         div { class: "mt-4",
-                            p { "Balance: {account.balance}" }
+                            p { "Balance: {account.balance} SOL" }
                             // List tokens and transactions, etc.
                         }
 
@@ -88,8 +102,12 @@ pub fn QueryAccountDialog(show_query_dialog: Signal<bool>) -> Element {
                                         TokenAccountCard {
                                             mint: token_account.mint(),
                                             ata_address: token_account.ata_address(),
+                                            owner: token_account.owner(),
                                             token_balance: token_account.balance(),
-                                            state: token_account.state()
+                                            amount: token_account.amount(),
+                                            state: token_account.state(),
+                                            program: token_account.program(),
+                                            extensions: token_account.extensions()
                                         }
                                     }
                                 }
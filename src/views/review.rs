@@ -0,0 +1,240 @@
+use dioxus::prelude::*;
+
+use crate::{
+    approve_transaction, copied_address, format_decimal, format_sol, preview_transaction,
+    token_symbol, ApprovedTransaction, CancelHandle, CopySvg, Loader, NotificationInfo, RpcClient,
+    RpcError, RpcResult, TransactionPreview, GLOBAL_MESSAGE, TRANSACTION_REVIEW,
+};
+
+/// Simulates the transaction at the front of [TRANSACTION_REVIEW] and only
+/// hands it to the wallet once the user approved what it would do
+#[component]
+pub fn TransactionReview() -> Element {
+    let mut preview = use_signal(|| Option::<RpcResult<TransactionPreview>>::None);
+    let mut approving = use_signal(|| false);
+    // The base64 wire transaction of a transaction that was only signed
+    let mut signed = use_signal(|| Option::<String>::None);
+
+    // Aborts the simulation of a transaction that is no longer reviewed
+    let cancel_handle = use_hook(CancelHandle::new);
+    let effect_cancel_handle = cancel_handle.clone();
+
+    use_effect(move || {
+        let prepared = TRANSACTION_REVIEW.read().front().cloned();
+
+        effect_cancel_handle.cancel();
+        preview.set(None);
+        signed.set(None);
+
+        let Some(prepared) = prepared else {
+            return;
+        };
+
+        let client = RpcClient::with_cancel_handle(effect_cancel_handle.clone());
+        spawn(async move {
+            match preview_transaction(&client, &prepared.tx).await {
+                Err(RpcError::Cancelled) => {}
+                outcome => preview.set(Some(outcome)),
+            }
+        });
+    });
+
+    use_drop(move || cancel_handle.cancel());
+
+    let Some(prepared) = TRANSACTION_REVIEW.read().front().cloned() else {
+        return rsx! {};
+    };
+    let queued = TRANSACTION_REVIEW.read().len();
+
+    if let Some(wire_transaction) = signed.read().clone() {
+        return rsx! {
+            SignedTransaction { description: prepared.description.clone(), wire_transaction, signed }
+        };
+    }
+
+    let can_approve = !*approving.read()
+        && preview
+            .read()
+            .as_ref()
+            .is_some_and(|preview| preview.as_ref().is_ok_and(TransactionPreview::can_approve));
+
+    let body = match preview.read().as_ref() {
+        None => rsx! {
+            div { class: "flex items-center text-sm", span{class:"mr-2", {Loader()}} "Simulating transaction..." }
+        },
+        Some(Err(error)) => rsx! {
+            p { class: "text-red-500 text-sm", "Failed to simulate the transaction: {error}" }
+        },
+        Some(Ok(preview)) => rsx! {
+            PreviewSummary { preview: preview.clone() }
+        },
+    };
+
+    rsx! {
+        div { class: "fixed z-20 top-0 left-0 flex flex-col w-full h-full bg-[rgba(0,0,0,0.6)] justify-center items-center text-black dark:text-white",
+            div { class: "flex flex-col w-[90%] sm:w-[80%] md:w-[70%] max-h-[80%] lg:w-[90%] max-w-screen-sm justify-start items-center bg-white dark:bg-[#0b0414] rounded-3xl p-5",
+                div { class: "flex flex-col w-full items-center mb-4",
+                    span { class: "text-2xl text-true-blue dark:text-white", "Review Transaction" }
+                    span { class: "text-sm mt-1", {prepared.description.as_str()} }
+                    if queued > 1 {
+                        span { class: "text-xs opacity-80", "{queued} transactions left to review" }
+                    }
+                }
+                div { class: "overflow-y-scroll w-full flex flex-col gap-3", {body} }
+                div { class: "flex w-full items-center justify-center gap-4 mt-4",
+                    button {
+                        disabled: *approving.read(),
+                        onclick: move |_| TRANSACTION_REVIEW.write().clear(),
+                        class: "flex text-sm px-4 py-1 items-center justify-center rounded-full border border-true-blue",
+                        "CANCEL"
+                    }
+                    button {
+                        disabled: !can_approve,
+                        onclick: move |_| {
+                            let prepared = prepared.clone();
+
+                            spawn(async move {
+                                approving.set(true);
+
+                                match approve_transaction(&prepared).await {
+                                    Ok(ApprovedTransaction::Sent { .. }) => {
+                                        GLOBAL_MESSAGE.write().push_back(NotificationInfo::new("Sent"));
+                                        TRANSACTION_REVIEW.write().pop_front();
+                                    }
+                                    // Stays in the queue until the signed transaction was copied
+                                    Ok(approved) => signed.set(approved.wire_transaction()),
+                                    // The transactions queued after it may depend on it
+                                    Err(error) => {
+                                        GLOBAL_MESSAGE.write().push_back(
+                                            NotificationInfo::error(format!("{} ERROR: {:?}", prepared.description.to_uppercase(), error))
+                                        );
+                                        TRANSACTION_REVIEW.write().clear();
+                                    }
+                                }
+
+                                approving.set(false);
+                            });
+                        },
+                        class: if can_approve { "bg-true-blue hover:bg-cobalt-blue text-white" } else { "bg-gray-400 text-white" },
+                        class: "flex text-sm px-4 py-1 items-center justify-center rounded-full",
+                        if *approving.read() {
+                            {Loader()} "Waiting for the wallet..."
+                        } else {
+                            "APPROVE"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Shows the wire form of a transaction the wallet only signed, it is
+/// removed from [TRANSACTION_REVIEW] once the user is done with it
+#[component]
+fn SignedTransaction(
+    description: String,
+    wire_transaction: String,
+    signed: Signal<Option<String>>,
+) -> Element {
+    let copied_transaction = wire_transaction.clone();
+
+    rsx! {
+        div { class: "fixed z-20 top-0 left-0 flex flex-col w-full h-full bg-[rgba(0,0,0,0.6)] justify-center items-center text-black dark:text-white",
+            div { class: "flex flex-col w-[90%] sm:w-[80%] md:w-[70%] max-h-[80%] lg:w-[90%] max-w-screen-sm justify-start items-center bg-white dark:bg-[#0b0414] rounded-3xl p-5",
+                div { class: "flex flex-col w-full items-center mb-4",
+                    span { class: "text-2xl text-true-blue dark:text-white", "Transaction Signed" }
+                    span { class: "text-sm mt-1", {description} }
+                    span { class: "text-xs opacity-80", "Base64 wire transaction, ready for sendTransaction" }
+                }
+                pre { class: "overflow-y-scroll w-full text-xs whitespace-pre-wrap break-all p-3 rounded-lg bg-gray-100 dark:bg-gray-800",
+                    {wire_transaction}
+                }
+                div { class: "flex w-full items-center justify-center gap-4 mt-4",
+                    button {
+                        onclick: move |_| {
+                            let copied_transaction = copied_transaction.clone();
+
+                            spawn(async move {
+                                if let Err(error) = copied_address(&copied_transaction).await {
+                                    GLOBAL_MESSAGE.write().push_back(NotificationInfo::error(format!("COPY ERROR: {:?}", error)));
+                                } else {
+                                    GLOBAL_MESSAGE.write().push_back(NotificationInfo::new("Copied to clipboard"));
+                                }
+                            });
+                        },
+                        class: "flex text-sm px-4 py-1 items-center justify-center rounded-full border border-true-blue",
+                        span { class: "flex w-[20px] mr-1", {CopySvg()} }
+                        "COPY"
+                    }
+                    button {
+                        onclick: move |_| {
+                            signed.set(None);
+                            TRANSACTION_REVIEW.write().pop_front();
+                        },
+                        class: "bg-true-blue hover:bg-cobalt-blue text-white flex text-sm px-4 py-1 items-center justify-center rounded-full",
+                        "DONE"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn PreviewSummary(preview: TransactionPreview) -> Element {
+    let fee = preview
+        .fee
+        .map(|fee| fee.to_string() + " SOL")
+        .unwrap_or(String::from("Unknown"));
+
+    rsx! {
+        if let Some(error) = preview.error.as_ref() {
+            div { class: "rounded-lg p-3 bg-red-100 text-red-800 text-sm",
+                p { class: "font-semibold", "The transaction would fail" }
+                p { class: "break-all", {error.as_str()} }
+            }
+        }
+
+        div { class: "flex flex-col w-full text-sm gap-1",
+            div { class: "flex w-full justify-between", span { "Fee" } span { {fee} } }
+            if let Some(units_consumed) = preview.units_consumed {
+                div { class: "flex w-full justify-between", span { "Compute units" } span { "{units_consumed}" } }
+            }
+        }
+
+        div { class: "flex flex-col w-full text-sm",
+            h2 { class: "text-lg text-true-blue dark:text-white mb-1", "Balance Changes" }
+            for change in preview.sol_changes.iter() {
+                div { class: "flex w-full justify-between border-b border-gray-500 py-1",
+                    span { "SOL" }
+                    span { class: "opacity-80", {format_sol(change.pre)} " → " {format_sol(change.post)} }
+                    span { {change.change()} }
+                }
+            }
+            for change in preview.token_changes.iter() {
+                div { class: "flex w-full justify-between border-b border-gray-500 py-1",
+                    span { {token_symbol(&change.mint)} }
+                    span { class: "opacity-80",
+                        {format_decimal(change.pre, change.decimals)} " → " {format_decimal(change.post, change.decimals)}
+                    }
+                    span { {change.change()} }
+                }
+            }
+            if preview.sol_changes.is_empty() && preview.token_changes.is_empty() {
+                p { "None of your balances change" }
+            }
+        }
+
+        div { class: "flex flex-col w-full",
+            h2 { class: "text-lg text-true-blue dark:text-white mb-1", "Logs" }
+            if preview.logs.is_empty() {
+                p { class: "text-sm", "The simulation did not log anything" }
+            } else {
+                pre { class: "text-xs whitespace-pre-wrap break-all p-3 rounded-lg bg-gray-100 dark:bg-gray-800",
+                    {preview.logs.join("\n")}
+                }
+            }
+        }
+    }
+}
@@ -1,15 +1,17 @@
 use dioxus::prelude::*;
 
 use crate::{
-    fetch_parser::send_sol_req, Loader, NotificationInfo, SendSvg, UserSvg, ACTIVE_CONNECTION,
-    GLOBAL_MESSAGE,
+    fetch_parser::prepare_sol_transfer, Lamports, Loader, NotificationInfo, SendSvg, UserSvg,
+    ACTIVE_CONNECTION, GLOBAL_MESSAGE, TRANSACTION_REVIEW,
 };
 
 #[component]
 pub fn SendSol(show_send_modal: Signal<bool>) -> Element {
     let mut loading = use_signal(|| false);
     let mut address = use_signal(|| Option::default());
-    let mut lamports = use_signal(|| 0u64);
+    let mut lamports = use_signal(|| Option::<Lamports>::None);
+    let mut amount_error = use_signal(|| Option::<String>::None);
+    let mut memo = use_signal(String::new);
 
     let mut public_key_bytes = [0u8; 32];
 
@@ -43,25 +45,42 @@ pub fn SendSol(show_send_modal: Signal<bool>) -> Element {
                     div { class: "overflow-y-scroll max-h-[90%] w-full mb-5 items-center justify-center flex flex-col",
                         div { class: "flex text-true-blue dark:text-white w-full items-center justify-center",
                             span{class:"w-[30px] flex mb-10 mr-2",{SendSvg()}}
-                            span{class:"flex mb-10 mr-2 text-3xl","Send Lamports"}
+                            span{class:"flex mb-10 mr-2 text-3xl","Send SOL"}
                         }
                         div { class: "flex flex-col w-3/5 mt-2 rounded-3x",
                             div { class: "flex w-full items-center rounded-xl p-1 bg-transparent",
                                 div { class: "shrink-0 select-none text-base text-true-blue dark:text-white sm:text-sm/6 mb-10","SOL" }
                                 input {
                                     oninput: move |event| {
-                                        if let Ok(data) = event.data.value().as_str().parse::<u64>(){
-                                            lamports.set(data);
+                                        let value = event.data.value();
+
+                                        // Parsed exactly, "1.25" is 1_250_000_000 lamports
+                                        match value.parse::<Lamports>() {
+                                            Ok(value) => {
+                                                lamports.set(Some(value));
+                                                amount_error.set(None);
+                                            }
+                                            Err(_) if value.trim().is_empty() => {
+                                                lamports.set(None);
+                                                amount_error.set(None);
+                                            }
+                                            Err(error) => {
+                                                lamports.set(None);
+                                                amount_error.set(Some(error.to_string()));
+                                            }
                                         }
                                     },
                                     class: "focus:outline-none mb-10 bg-transparent border-b-2 border-true-blue block min-w-0 grow ml-2 text-black dark:text-white placeholder:text-gray-400 sm:text-sm/6",
                                     id: "lamports",
-                                    min: "0",
                                     name: "lamports",
-                                    placeholder: "50000",
-                                    r#type: "number",
+                                    placeholder: "0.05",
+                                    inputmode: "decimal",
+                                    r#type: "text",
                                 }
                             }
+                            if let Some(error) = amount_error.read().as_ref() {
+                                p { class: "text-red-500 text-xs -mt-8 mb-8", "{error}" }
+                            }
                             div { class: "flex items-center rounded-xl p-1 bg-transparent",
                                 div { class: "shrink-0 select-none text-base text-gray-500 sm:text-sm/6", span {class:"flex w-[20px]", {UserSvg()}  } }
                                 input {
@@ -77,34 +96,55 @@ pub fn SendSol(show_send_modal: Signal<bool>) -> Element {
                                     placeholder: "Enter Recipient Address",
                                 }
                             }
+                            div { class: "flex items-center rounded-xl p-1 mt-4 bg-transparent",
+                                div { class: "shrink-0 select-none text-base text-true-blue dark:text-white sm:text-sm/6", "MEMO" }
+                                input {
+                                    oninput: move |event| memo.set(event.data.value()),
+                                    class: "w-full focus:outline-none bg-transparent border-b-2 border-true-blue block min-w-0 grow ml-2 text-black dark:text-white placeholder:text-gray-400 sm:text-sm/6",
+                                    id: "memo",
+                                    name: "memo",
+                                    r#type: "text",
+                                    placeholder: "Optional, some exchanges require one",
+                                }
+                            }
                         }
                         div { class: "flex w-full items-center justify-center mt-4",
-                            button {disabled:*loading.read() && address.read().is_none(),
+                            button {disabled:*loading.read() || address.read().is_none() || lamports.read().is_none(),
                                 onclick:move|_|{
+                                    let Some(lamports) = *lamports.read() else {
+                                        return;
+                                    };
+
                                     spawn(async move {
                                         loading.set(true);
 
-                                        if let Err(error) = send_sol_req(&address.read().as_ref().cloned().unwrap_or_default(),
-                                            *lamports.read(),
+                                        let memo = memo.read().trim().to_string();
+
+                                        match prepare_sol_transfer(&address.read().as_ref().cloned().unwrap_or_default(),
+                                            lamports,
+                                            Some(memo.as_str()).filter(|memo| !memo.is_empty()),
                                             public_key_bytes
                                         ).await {
-                                            GLOBAL_MESSAGE.write().push_back(
-                                                NotificationInfo::error(format!("SEND SOL ERROR: {:?}", error))
-                                            );
+                                            Ok(prepared) => {
+                                                TRANSACTION_REVIEW.write().push_back(prepared);
+                                            }
+                                            Err(error) => {
+                                                GLOBAL_MESSAGE.write().push_back(
+                                                    NotificationInfo::error(format!("SEND SOL ERROR: {:?}", error))
+                                                );
+                                            }
                                         }
 
                                         loading.set(false);
                                         show_send_modal.set(false);
 
-                                        GLOBAL_MESSAGE.write().push_back(NotificationInfo::new("Sent"));
-
                                         show_send_modal.set(false);
                                     });
                                 },
                                 class:if *loading.read() {""}else{"bg-true-blue hover:bg-cobalt-blue"},
                                 class:"flex text-sm mb-10  text-white text-black px-4 py-1 items-center justify-center rounded-full",
                                 if *loading.read() {
-                                    {Loader()} span {class:"text-true-blue", "Sending SOL..."}
+                                    {Loader()} span {class:"text-true-blue", "Preparing transfer..."}
                                 }else {
                                     "SEND SOL"
                                 }
@@ -0,0 +1,169 @@
+use dioxus::prelude::*;
+
+use crate::{
+    prepare_token_transfer, token_metadata, Loader, NotificationInfo, SendSvg, TokenAmount,
+    TokenProgram, TokenTransfer, UserSvg, ACTIVE_CONNECTION, GLOBAL_MESSAGE, TRANSACTION_REVIEW,
+};
+
+#[component]
+pub fn SendToken(
+    show_send_modal: Signal<bool>,
+    mint: String,
+    ata_address: String,
+    balance: TokenAmount,
+    program: TokenProgram,
+) -> Element {
+    let mut loading = use_signal(|| false);
+    let mut address = use_signal(|| Option::<String>::None);
+    let mut amount = use_signal(|| Option::<TokenAmount>::None);
+    let mut amount_error = use_signal(|| Option::<String>::None);
+
+    let mut public_key_bytes = [0u8; 32];
+
+    if let Ok(wallet_account) = ACTIVE_CONNECTION.read().connected_account() {
+        public_key_bytes = wallet_account.public_key();
+    }
+
+    let symbol = token_metadata(&mint)
+        .map(|metadata| metadata.symbol)
+        .filter(|symbol| !symbol.is_empty())
+        .unwrap_or(String::from("Tokens"));
+
+    if *show_send_modal.read() {
+        rsx! {
+            div { class: "fixed z-10 top-0 left-0 flex flex-col w-full h-full bg-[rgba(0,0,0,0.6)] justify-center items-center text-black dark:text-white",
+                div { class: "flex flex-col w-[90%] sm:w-[80%] md:w-[70%] min-h-64 max-h-[60%] lg:w-[90%] max-w-screen-sm justify-start items-center bg-white dark:bg-[#0b0414] rounded-3xl",
+                    div { class: "flex w-full justify-end items-center p-5",
+                        button {
+                            onclick:move|_|{show_send_modal.set(false)},
+                            class: "wallet-adapter-modal-button-close w-[30px] items-center justify-center",
+                            svg {
+                                fill: "none",
+                                view_box: "0 0 24 24",
+                                xmlns: "http://www.w3.org/2000/svg",
+                                path {
+                                    d: "m15 9.00004-6 5.99996m6 0-6-5.99996m3 11.99996c4.9706 0 9-4.0294 9-9 0-4.97056-4.0294-9-9-9-4.97056 0-9 4.02944-9 9 0 4.9706 4.02944 9 9 9z",
+                                    stroke: "#a6c1ee",
+                                    stroke_linecap: "round",
+                                    stroke_linejoin: "round",
+                                    stroke_width: "2",
+                                }
+                            }
+                        }
+                    }
+                    div { class: "overflow-y-scroll max-h-[90%] w-full mb-5 items-center justify-center flex flex-col",
+                        div { class: "flex text-true-blue dark:text-white w-full items-center justify-center",
+                            span{class:"w-[30px] flex mb-10 mr-2",{SendSvg()}}
+                            span{class:"flex mb-10 mr-2 text-3xl","Send {symbol}"}
+                        }
+                        div { class: "flex flex-col w-3/5 mt-2 rounded-3x",
+                            div { class: "flex w-full items-center rounded-xl p-1 bg-transparent",
+                                div { class: "shrink-0 select-none text-base text-true-blue dark:text-white sm:text-sm/6 mb-10", {symbol.as_str()} }
+                                input {
+                                    oninput: move |event| {
+                                        let value = event.data.value();
+
+                                        // Parsed with the decimals of the mint, the program rejects any other
+                                        match TokenAmount::parse(&value, balance.decimals()) {
+                                            Ok(value) if value.raw() > balance.raw() => {
+                                                amount.set(None);
+                                                amount_error.set(Some(format!("The balance is only {balance}")));
+                                            }
+                                            Ok(value) => {
+                                                amount.set(Some(value));
+                                                amount_error.set(None);
+                                            }
+                                            Err(_) if value.trim().is_empty() => {
+                                                amount.set(None);
+                                                amount_error.set(None);
+                                            }
+                                            Err(error) => {
+                                                amount.set(None);
+                                                amount_error.set(Some(error.to_string()));
+                                            }
+                                        }
+                                    },
+                                    class: "focus:outline-none mb-10 bg-transparent border-b-2 border-true-blue block min-w-0 grow ml-2 text-black dark:text-white placeholder:text-gray-400 sm:text-sm/6",
+                                    id: "token-amount",
+                                    name: "token-amount",
+                                    placeholder: "{balance}",
+                                    inputmode: "decimal",
+                                    r#type: "text",
+                                }
+                            }
+                            if let Some(error) = amount_error.read().as_ref() {
+                                p { class: "text-red-500 text-xs -mt-8 mb-8", "{error}" }
+                            }
+                            div { class: "flex items-center rounded-xl p-1 bg-transparent",
+                                div { class: "shrink-0 select-none text-base text-gray-500 sm:text-sm/6", span {class:"flex w-[20px]", {UserSvg()}  } }
+                                input {
+                                    oninput: move |event| {
+                                        let data = event.data.value();
+                                        address.write().replace(data);
+                                    },
+                                    class: "w-full focus:outline-none bg-transparent border-b-2 border-true-blue block min-w-0 grow ml-2 text-black dark:text-white placeholder:text-gray-400 sm:text-sm/6",
+                                    id: "token-recipient",
+                                    name: "token-recipient",
+                                    r#type: "text",
+                                    placeholder: "Enter Recipient Wallet Address",
+                                }
+                            }
+                            p { class: "text-xs mt-2 opacity-80",
+                                "The tokens go to the associated token account of the recipient, it is created if needed"
+                            }
+                        }
+                        div { class: "flex w-full items-center justify-center mt-4",
+                            button {disabled:*loading.read() || address.read().is_none() || amount.read().is_none_or(|amount| amount.is_zero()),
+                                onclick:move|_|{
+                                    let Some(amount) = *amount.read() else {
+                                        return;
+                                    };
+
+                                    let transfer = TokenTransfer::new(
+                                        &ata_address,
+                                        &mint,
+                                        &address.read().as_ref().cloned().unwrap_or_default(),
+                                        amount,
+                                        program,
+                                    );
+
+                                    spawn(async move {
+                                        loading.set(true);
+
+                                        let outcome = match transfer {
+                                            Ok(transfer) => prepare_token_transfer(&transfer, public_key_bytes).await,
+                                            Err(error) => Err(error),
+                                        };
+
+                                        match outcome {
+                                            Ok(prepared) => {
+                                                TRANSACTION_REVIEW.write().push_back(prepared);
+                                            }
+                                            Err(error) => {
+                                                GLOBAL_MESSAGE.write().push_back(
+                                                    NotificationInfo::error(format!("SEND TOKEN ERROR: {:?}", error))
+                                                );
+                                            }
+                                        }
+
+                                        loading.set(false);
+                                        show_send_modal.set(false);
+                                    });
+                                },
+                                class:if *loading.read() {""}else{"bg-true-blue hover:bg-cobalt-blue"},
+                                class:"flex text-sm mb-10  text-white text-black px-4 py-1 items-center justify-center rounded-full",
+                                if *loading.read() {
+                                    {Loader()} span {class:"text-true-blue", "Preparing transfer..."}
+                                }else {
+                                    "SEND"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    } else {
+        rsx! {}
+    }
+}
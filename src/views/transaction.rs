@@ -0,0 +1,182 @@
+use dioxus::prelude::*;
+
+use crate::{
+    format_sol, format_timestamp, link_target_blank, load_transaction, shortened_address_link,
+    utils::format_tx_url, CancelHandle, CheckSvg, DecodedInstruction, ErrorSvg, Loader, RpcClient,
+    RpcError, RpcResult, TransactionResponse, CLUSTER_STORAGE,
+};
+
+/// A transaction of the active cluster. The explorer cannot see localnet
+/// and private clusters so transactions are loaded from the cluster itself.
+#[component]
+pub fn TransactionDetail(signature: String) -> Element {
+    let mut transaction = use_signal(|| Option::<RpcResult<Option<TransactionResponse>>>::None);
+
+    // Aborts the request when the page is left before it answers
+    let cancel_handle = use_hook(CancelHandle::new);
+    let effect_cancel_handle = cancel_handle.clone();
+
+    use_effect(use_reactive(&signature, move |signature| {
+        // Reloads the transaction when the active cluster changes
+        let _ = CLUSTER_STORAGE.read();
+
+        effect_cancel_handle.cancel();
+        transaction.set(None);
+
+        let client = RpcClient::with_cancel_handle(effect_cancel_handle.clone());
+        spawn(async move {
+            match load_transaction(&client, &signature).await {
+                Err(RpcError::Cancelled) => {}
+                outcome => transaction.set(Some(outcome)),
+            }
+        });
+    }));
+
+    use_drop(move || cancel_handle.cancel());
+
+    let cluster_name = CLUSTER_STORAGE.read().active_cluster().name().to_string();
+
+    let body = match transaction.read().as_ref() {
+        None => rsx! {
+            div { class: "flex items-center", span{class:"mr-2", {Loader()}} "Loading transaction..." }
+        },
+        Some(Err(error)) => rsx! {
+            p { class: "text-red-500", "Failed to load the transaction: {error}" }
+        },
+        Some(Ok(None)) => rsx! {
+            p { "The transaction was not found on {cluster_name}" }
+        },
+        Some(Ok(Some(transaction))) => rsx! {
+            TransactionSummary { transaction: transaction.clone() }
+        },
+    };
+
+    rsx! {
+        div { class: "flex w-full flex-col items-center mt-4 mb-10 px-4",
+            div { class: "flex flex-col w-full lg:w-[80%] gap-4",
+                div { class: "flex flex-col w-full",
+                    h1 { class: "text-2xl text-true-blue dark:text-white", "Transaction" }
+                    span { class: "text-sm break-all", {signature.as_str()} }
+                    span { class: "text-xs mt-1", {link_target_blank(&format_tx_url(&signature), "View in explorer")} }
+                }
+                {body}
+            }
+        }
+    }
+}
+
+#[component]
+fn TransactionSummary(transaction: TransactionResponse) -> Element {
+    let status = match transaction.error() {
+        None => rsx! {
+            span { class: "w-[15px] mr-2", {CheckSvg()} } "SUCCESS"
+        },
+        Some(error) => rsx! {
+            span { class: "w-[15px] mr-2", {ErrorSvg()} } "FAILED: {error}"
+        },
+    };
+
+    let fee = transaction
+        .fee()
+        .map(|fee| format_sol(fee) + " SOL")
+        .unwrap_or(String::from("Unknown"));
+    let version = transaction
+        .version
+        .as_ref()
+        .map(|version| version.to_string())
+        .unwrap_or(String::from("LEGACY"));
+    let compute_units = transaction
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.compute_units_consumed);
+
+    rsx! {
+        div { class: "flex flex-col w-full rounded-lg p-5 bg-true-blue text-white gap-1",
+            div { class: "flex items-center", {status} }
+            div { class: "text-sm", "Fee: {fee}" }
+            div { class: "text-sm", "Slot: {transaction.slot}" }
+            if let Some(block_time) = transaction.block_time {
+                div { class: "text-sm", "Time: " {format_timestamp(block_time)} }
+            }
+            if let Some(compute_units) = compute_units {
+                div { class: "text-sm", "Compute units: {compute_units}" }
+            }
+            div { class: "flex",
+                span { class: "bg-blue-100 text-blue-800 text-xs font-semibold px-2.5 py-0.5 rounded-full dark:bg-blue-200 dark:text-blue-800",
+                    {version}
+                }
+            }
+        }
+
+        div { class: "flex flex-col w-full",
+            h2 { class: "text-xl text-true-blue dark:text-white mb-2", "SOL Balance Changes" }
+            if transaction.sol_balance_changes().is_empty() {
+                p { class: "text-sm", "No SOL balance changed" }
+            }
+            for change in transaction.sol_balance_changes() {
+                div { class: "flex w-full justify-between text-sm border-b border-gray-500 py-1",
+                    span { {shortened_address_link(&change.account)} }
+                    span { {change.change()} " SOL" }
+                    span { class: "opacity-80", {format_sol(change.post)} " SOL" }
+                }
+            }
+        }
+
+        div { class: "flex flex-col w-full",
+            h2 { class: "text-xl text-true-blue dark:text-white mb-2", "Token Balance Changes" }
+            if transaction.token_balance_changes().is_empty() {
+                p { class: "text-sm", "No token balance changed" }
+            }
+            for change in transaction.token_balance_changes() {
+                div { class: "flex w-full justify-between text-sm border-b border-gray-500 py-1",
+                    span { {shortened_address_link(&change.account)} }
+                    span { "Mint " {shortened_address_link(&change.mint)} }
+                    span { {change.change()} }
+                }
+            }
+        }
+
+        div { class: "flex flex-col w-full",
+            h2 { class: "text-xl text-true-blue dark:text-white mb-2", "Instructions" }
+            for (index, instruction) in transaction.instructions().into_iter().enumerate() {
+                InstructionCard { position: format!("#{}", index + 1), instruction }
+            }
+        }
+
+        div { class: "flex flex-col w-full",
+            h2 { class: "text-xl text-true-blue dark:text-white mb-2", "Logs" }
+            if transaction.log_messages().is_empty() {
+                p { class: "text-sm", "The node did not return any log" }
+            } else {
+                pre { class: "text-xs whitespace-pre-wrap break-all p-4 rounded-lg bg-gray-100 dark:bg-gray-800",
+                    {transaction.log_messages().join("\n")}
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn InstructionCard(position: String, instruction: DecodedInstruction) -> Element {
+    rsx! {
+        div { class: "flex flex-col w-full rounded-lg p-4 mb-2 border border-true-blue",
+            div { class: "flex items-center justify-between",
+                span { class: "font-semibold", "{position} {instruction.name}" }
+                span { class: "bg-blue-100 text-blue-800 text-xs font-semibold px-2.5 py-0.5 rounded-full dark:bg-blue-200 dark:text-blue-800",
+                    {instruction.program_name()}
+                }
+            }
+            for (name, value) in instruction.fields.iter() {
+                div { class: "flex w-full justify-between text-sm mt-1",
+                    span { class: "opacity-80 mr-4", {name.as_str()} }
+                    span { class: "break-all text-right", {value.as_str()} }
+                }
+            }
+            for (index, inner) in instruction.inner.iter().enumerate() {
+                div { class: "ml-4 mt-2",
+                    InstructionCard { position: format!("{position}.{}", index + 1), instruction: inner.clone() }
+                }
+            }
+        }
+    }
+}